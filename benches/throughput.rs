@@ -0,0 +1,104 @@
+//! Throughput of the targets when many threads log to them at once.
+//!
+//! The entries are written to a sink which discards them, so that only the cost of
+//! the logger itself is measured.
+
+use std::sync::OnceLock;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use prologue_logger::{Entry, LogItem, PrologueLogger, Target, TargetList};
+use prologue_logger::sink::Sink;
+
+const THREADS: [usize; 4] = [1, 2, 4, 8];
+
+/// Sink discarding every entry.
+struct NullSink;
+impl Sink for NullSink {
+    fn write(&self, _item: &LogItem) -> prologue_logger::error::Result<()> {
+        Ok(())
+    }
+
+    fn flush(&self) -> prologue_logger::error::Result<()> {
+        Ok(())
+    }
+}
+
+/// Runs `f` on `threads` threads at once, `iters` times each, and returns the elapsed time.
+fn run_parallel<F: Fn() + Sync>(threads: usize, iters: u64, f: F) -> Duration {
+    let start = Instant::now();
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| (0..iters).for_each(|_| f()));
+        }
+    });
+    start.elapsed()
+}
+
+fn target_list() -> TargetList {
+    let target_list = TargetList::new();
+    for index in 0..100 {
+        target_list.add_target(Target::with_sink(format!("crate_{}", index), NullSink)).unwrap();
+    }
+    target_list
+}
+
+fn log_to_target(c: &mut Criterion) {
+    let mut group = c.benchmark_group("log_to_target");
+    let target = Target::with_sink("my-target", NullSink);
+    for threads in THREADS {
+        group.throughput(Throughput::Elements(threads as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(threads), &threads, |b, &threads| {
+            b.iter_custom(|iters| run_parallel(threads, iters, || {
+                Entry::new_warning("something needs your attention")
+                    .log_to_target(&target)
+                    .unwrap();
+            }));
+        });
+    }
+    group.finish();
+}
+
+fn route(c: &mut Criterion) {
+    let mut group = c.benchmark_group("route");
+    let target_list = target_list();
+    for threads in THREADS {
+        group.throughput(Throughput::Elements(threads as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(threads), &threads, |b, &threads| {
+            b.iter_custom(|iters| run_parallel(threads, iters, || {
+                target_list.route("crate_42::parser::lexer").unwrap();
+            }));
+        });
+    }
+    group.finish();
+}
+
+fn log_macro(c: &mut Criterion) {
+    static LOGGER: OnceLock<TargetList> = OnceLock::new();
+    LOGGER.get_or_init(|| {
+        let mut builder = PrologueLogger::builder()
+            .stderr(false)
+            .sink(NullSink);
+        for index in 0..100 {
+            builder = builder.target(format!("crate_{}", index));
+        }
+        // The targets are flushed when the guard is dropped, which is fine with `NullSink`.
+        let guard = builder.init();
+        (*guard).clone()
+    });
+
+    let mut group = c.benchmark_group("log_macro");
+    for threads in THREADS {
+        group.throughput(Throughput::Elements(threads as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(threads), &threads, |b, &threads| {
+            b.iter_custom(|iters| run_parallel(threads, iters, || {
+                log::warn!(target: "crate_42::parser::lexer", "something needs your attention");
+            }));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, log_to_target, route, log_macro);
+criterion_main!(benches);
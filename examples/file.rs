@@ -17,7 +17,7 @@ impl log::Log for FileLogger {
         if self.enabled(record.metadata()) {
             let mut file = self.file.lock().unwrap();
             let contents = format!("{}", record.args());
-            file.write(contents.as_bytes()).unwrap();
+            file.write_all(contents.as_bytes()).unwrap();
         }
     }
 
@@ -39,17 +39,11 @@ impl FileLogger {
     }
 }
 
-fn main() -> prologue_logger::error::Result<()> {
-    FileLogger::new("file.log")
-        .unwrap()
-        .register()
-        .unwrap();
-
-    Entry::new_warning("starting `file.rs` -- the following is NOT generated by `cargo`")
-        .log("file");
-    Task::new("Running", "example `file.rs`")
-        .log("file");
-    Entry::new_warning("this is a warning line")
+/// Builds the entries logged by this example, in order.
+pub fn entries() -> prologue_logger::error::Result<(Entry, Task, Entry)> {
+    let starting = Entry::new_warning("starting `file.rs` -- the following is NOT generated by `cargo`");
+    let running = Task::new("Running", "example `file.rs`");
+    let warning = Entry::new_warning("this is a warning line")
         .named_source("examples/file.rs", 8, 36)
         .new_line(8, "    let entry = Entry::new_warning(\"this is a warning line\")")
         .annotate_help(9, 5, "this is the variable")?
@@ -62,8 +56,20 @@ fn main() -> prologue_logger::error::Result<()> {
         .note("this is not the actual source code")
         .help("to see the actual source code for this example,\nsee `examples/file.rs`")
         .note("this output is generated by `prologue-logger` and NOT by `cargo`")
-        .finish()
-        .log("file");
+        .finish();
+    Ok((starting, running, warning))
+}
+
+fn main() -> prologue_logger::error::Result<()> {
+    FileLogger::new("file.log")
+        .unwrap()
+        .register()
+        .unwrap();
+
+    let (starting, running, warning) = entries()?;
+    starting.log("file");
+    running.log("file");
+    warning.log("file");
 
     Ok(())
-}
\ No newline at end of file
+}
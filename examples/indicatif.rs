@@ -115,7 +115,7 @@ impl Tasks {
     pub fn update_pb(&self, current_tasks: &std::sync::MutexGuard<Vec<&'static str>>) {
         let mut task_string = String::new();
         for task in current_tasks.iter() {
-            let comma = if task_string.len() == 0 { "" } else { "," };
+            let comma = if task_string.is_empty() { "" } else { "," };
             task_string = format!("{}{}{}", task_string, comma, task);
         }
         self.progress_bar.set_message(task_string);
@@ -0,0 +1,29 @@
+use prologue_logger::{Entry, Task};
+use prologue_logger::svg::SvgImage;
+
+fn main() -> prologue_logger::error::Result<()> {
+    let mut image = SvgImage::new();
+
+    image.add_task(&Task::new("Running", "example `svg.rs`"));
+    image.add_entry(&Entry::new_warning("this is a warning line")
+        .named_source("examples/svg.rs", 8, 36)
+        .new_line(8, "    let entry = Entry::new_warning(\"this is a warning line\")")
+        .annotate_help(9, 5, "this is the variable")?
+        .annotate_help(17, 18, "this is the invoking function")?
+        .annotate_note(36, 24, "this is the text")?
+        .new_line(9, "    .bright()")
+        .new_line(10, "    .source(source)")
+        .new_line(11, "    .forward_to_stderr();")
+        .annotate_warn(6, 17, "this function does not increase the warning count")?
+        .note("this is not the actual source code")
+        .help("to see the actual source code for this example,\nsee `examples/svg.rs`")
+        .note("this output is generated by `prologue-logger` and NOT by `cargo`")
+        .finish());
+    image.add_entry(&Entry::new_warning("`example/svg.rs` (example) generated 1 warning"));
+
+    // The image in the `README.md` file is generated by this example.
+    std::fs::write("screenshots/svg.svg", image.to_string())
+        .expect("could not write `screenshots/svg.svg`");
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use prologue_logger::TargetList;
+use prologue_logger::subscriber::PrologueLayer;
+use tracing_subscriber::layer::SubscriberExt;
+
+fn main() -> prologue_logger::error::Result<()> {
+    let target_list = TargetList::new();
+    target_list.create_target("tracing")?;
+    target_list.create_target("")?;
+
+    let subscriber = tracing_subscriber::registry()
+        .with(PrologueLayer::new(target_list.clone())
+            .enter_tasks(true)
+            .close_tasks("Finished"));
+    tracing::subscriber::set_global_default(subscriber)
+        .expect("could not set the global subscriber");
+
+    tracing::warn!(target: "tracing", "starting `tracing.rs` -- the following is NOT generated by `cargo`");
+    {
+        let _span = tracing::info_span!(target: "tracing", "Checking", krate = "example `tracing.rs`").entered();
+        tracing::warn!(
+            target: "tracing::config",
+            file = "examples/tracing.rs",
+            line = 20,
+            col = 9,
+            code = "W0001",
+            "this warning has a location"
+        );
+        tracing::info!(target: "tracing", task = "Running", "a task inside a span");
+    }
+
+    let target = target_list.find("tracing").unwrap();
+    target.if_warnings(|count| {
+        tracing::warn!(target: "", "`example/tracing.rs` (example) generated {} warning{}", count, if count > 1 { "s" } else { "" });
+        Ok(())
+    })?;
+
+    Ok(())
+}
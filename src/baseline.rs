@@ -0,0 +1,331 @@
+//! Baselines of known entries, to adopt a linter on an existing code base.
+//!
+//! A [`Baseline`] is a list of entries, identified by their
+//! [line-independent fingerprint](Fingerprint::of_entry_ignoring_lines) along with their file,
+//! code and message. When a baseline is assigned to a [`Target`], the entries it contains
+//! are suppressed, i.e. neither written nor counted as warnings or errors, while the new ones
+//! are reported as usual. Each entry of the baseline suppresses at most one logged entry.
+//!
+//! Meanwhile, the baseline records every entry logged in the current run,
+//! so that an updated baseline can be written at the end of the run with
+//! [`recorded`](Baseline::recorded), and the entries of the baseline which did not appear
+//! again can be reported with [`log_fixed_to_target`](Baseline::log_fixed_to_target).
+//!
+//! Baselines are stored as text files with one entry per line: the fingerprint, the file,
+//! the code and the message, separated by tabs. A missing file or code is written as `-`,
+//! while backslashes, tabs, line breaks and a field consisting of a literal `-` are escaped
+//! with a backslash. Empty lines and lines starting with `#` are ignored.
+//!
+//! # Example
+//! ```
+//! # fn main() -> prologue_logger::error::Result<()> {
+//! # use prologue_logger::{Entry, Target};
+//! # use prologue_logger::baseline::Baseline;
+//! # use prologue_logger::capture::Capture;
+//! let unused_key = |line| Entry::new_warning("unused key `edition`")
+//!     .named_source("Config.toml", line, 1)
+//!     .finish();
+//!
+//! // The first run writes the baseline.
+//! let baseline = Baseline::new();
+//! let target = Target::with_capture("my-target", Capture::new());
+//! target.set_baseline(Some(baseline.clone()));
+//! unused_key(3).log_to_target(&target)?;
+//! let text = baseline.recorded().to_string();
+//!
+//! // Later runs load it, even if the known entry moved to another line.
+//! let baseline: Baseline = text.parse()?;
+//! let capture = Capture::new();
+//! let target = Target::with_capture("my-target", capture.clone());
+//! target.set_baseline(Some(baseline.clone()));
+//! unused_key(5).log_to_target(&target)?;
+//! Entry::new_warning("unused key `authors`")
+//!     .log_to_target(&target)?;
+//!
+//! assert_eq!(target.warning_count(), 1);
+//! assert_eq!(target.baselined_count(), 1);
+//! assert_eq!(capture.text(), "warning: unused key `authors`\n");
+//! # Ok(()) }
+//! ```
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+use crate::{Entry, LogItem, Target};
+use crate::error::{Error, ErrorKind, Result};
+use crate::fingerprint::Fingerprint;
+
+/// Header written at the beginning of a baseline file.
+const HEADER: &str = "# prologue-logger baseline: fingerprint, file, code, message";
+
+/// An entry of a [`Baseline`].
+///
+/// The [`Display`] implementation produces the line of the entry in a baseline file.
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+pub struct BaselineEntry {
+    fingerprint: Fingerprint,
+    file: Option<String>,
+    code: Option<String>,
+    message: String
+}
+impl Display for BaselineEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f, "{}\t{}\t{}\t{}", self.fingerprint,
+            self.file.as_deref().map(escape_optional).unwrap_or_else(|| "-".to_owned()),
+            self.code.as_deref().map(escape_optional).unwrap_or_else(|| "-".to_owned()),
+            escape(&self.message)
+        )
+    }
+}
+impl BaselineEntry {
+    /// Creates the baseline entry of an item, or returns `None` for a [`Task`](crate::Task).
+    ///
+    /// The file, code and message of a [`MultiEntry`](crate::MultiEntry) are the ones
+    /// of its first entry.
+    pub fn of_item(item: &LogItem) -> Option<BaselineEntry> {
+        let fingerprint = Fingerprint::of_item_ignoring_lines(item)?;
+        let entry = item.primary_entry()?;
+        Some(BaselineEntry {
+            fingerprint,
+            file: entry.file().map(|file| file.to_string_lossy().into_owned()),
+            code: entry.error_code().map(str::to_owned),
+            message: entry.message().to_owned()
+        })
+    }
+
+    /// Returns the line-independent fingerprint of the entry.
+    pub fn fingerprint(&self) -> Fingerprint {
+        self.fingerprint
+    }
+
+    /// Returns the file of the entry, if any.
+    pub fn file(&self) -> Option<&str> {
+        self.file.as_deref()
+    }
+
+    /// Returns the code of the entry, if any, like [`Entry::error_code`](crate::Entry::error_code).
+    pub fn error_code(&self) -> Option<&str> {
+        self.code.as_deref()
+    }
+
+    /// Returns the message of the entry.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Parses a line of a baseline file.
+    fn parse(line: &str) -> Option<BaselineEntry> {
+        let fields = line.split('\t').collect::<Vec<_>>();
+        let optional = |field: &str| if field == "-" { Some(None) } else { unescape(field).map(Some) };
+        match fields[..] {
+            [fingerprint, file, code, message] => Some(BaselineEntry {
+                fingerprint: fingerprint.parse().ok()?,
+                file: optional(file)?,
+                code: optional(code)?,
+                message: unescape(message)?
+            }),
+            _ => None
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    known: Vec<BaselineEntry>,
+    /// Indices of the known entries which did not appear yet, by fingerprint.
+    remaining: HashMap<Fingerprint, Vec<usize>>,
+    recorded: Vec<BaselineEntry>
+}
+
+/// List of known entries, suppressed when logged again; see the [module documentation](self).
+///
+/// Clones of a `Baseline` share the same state, therefore a baseline can be assigned
+/// to many targets, e.g. all the targets of a [`TargetList`](crate::TargetList).
+#[derive(Clone, Debug, Default)]
+pub struct Baseline {
+    state: Arc<Mutex<State>>
+}
+impl Display for Baseline {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", HEADER)?;
+        for entry in self.state.lock().unwrap().known.iter() {
+            writeln!(f, "{}", entry)?;
+        }
+        Ok(())
+    }
+}
+impl FromStr for Baseline {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Baseline> {
+        Baseline::parse(text)
+    }
+}
+impl Baseline {
+    /// Creates an empty baseline, e.g. to record the entries of a first run.
+    pub fn new() -> Baseline {
+        Default::default()
+    }
+
+    /// Creates a baseline containing the given entries.
+    pub fn from_entries<I: IntoIterator<Item = BaselineEntry>>(entries: I) -> Baseline {
+        let known = entries.into_iter().collect::<Vec<_>>();
+        let mut remaining = HashMap::<_, Vec<_>>::new();
+        for (index, entry) in known.iter().enumerate().rev() {
+            remaining.entry(entry.fingerprint).or_default().push(index);
+        }
+        Baseline { state: Arc::new(Mutex::new(State { known, remaining, recorded: Vec::new() })) }
+    }
+
+    /// Parses the contents of a baseline file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ErrorKind::InvalidBaseline`] with the number of the first invalid line.
+    pub fn parse(text: &str) -> Result<Baseline> {
+        let entries = text.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'))
+            .map(|(index, line)| BaselineEntry::parse(line).ok_or_else(|| ErrorKind::InvalidBaseline(index + 1).into()))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Baseline::from_entries(entries))
+    }
+
+    /// Loads a baseline file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ErrorKind::IoError`] if the file cannot be read,
+    /// or [`ErrorKind::InvalidBaseline`] if it cannot be parsed.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Baseline> {
+        Baseline::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Writes the baseline to a file, replacing its contents.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        std::fs::write(path, self.to_string())?;
+        Ok(())
+    }
+
+    /// Returns the entries of the baseline.
+    pub fn entries(&self) -> Vec<BaselineEntry> {
+        self.state.lock().unwrap().known.clone()
+    }
+
+    /// Records `item` in the current run and returns `true` if it matches an entry of the
+    /// baseline which was not matched yet, i.e. if it is a known entry to suppress.
+    ///
+    /// Tasks are never known.
+    pub fn is_known(&self, item: &LogItem) -> bool {
+        let entry = match BaselineEntry::of_item(item) {
+            Some(entry) => entry,
+            None => return false
+        };
+        let mut state = self.state.lock().unwrap();
+        let known = state.remaining.get_mut(&entry.fingerprint)
+            .and_then(Vec::pop)
+            .is_some();
+        state.recorded.push(entry);
+        known
+    }
+
+    /// Returns a new baseline containing the entries recorded in the current run,
+    /// including the suppressed ones.
+    pub fn recorded(&self) -> Baseline {
+        Baseline::from_entries(self.state.lock().unwrap().recorded.clone())
+    }
+
+    /// Returns the entries of the baseline which did not appear in the current run.
+    pub fn fixed(&self) -> Vec<BaselineEntry> {
+        let state = self.state.lock().unwrap();
+        let mut fixed = state.remaining.values().flatten().copied().collect::<Vec<_>>();
+        fixed.sort_unstable();
+        fixed.into_iter().map(|index| state.known[index].clone()).collect()
+    }
+
+    /// Logs a note to `target` for every entry of the baseline which did not appear
+    /// in the current run, after a note with their number; does nothing if there are none.
+    ///
+    /// # Example
+    /// ```
+    /// # fn main() -> prologue_logger::error::Result<()> {
+    /// # use prologue_logger::{Entry, LogItem, Target};
+    /// # use prologue_logger::baseline::{Baseline, BaselineEntry};
+    /// # use prologue_logger::capture::Capture;
+    /// let fixed = Entry::new_warning("unused key `edition`")
+    ///     .code("W0001")
+    ///     .named_source("Config.toml", 3, 1)
+    ///     .finish();
+    /// let baseline = Baseline::from_entries(BaselineEntry::of_item(&LogItem::from(fixed)));
+    ///
+    /// let capture = Capture::new();
+    /// let target = Target::with_capture("my-target", capture.clone());
+    /// baseline.log_fixed_to_target(&target)?;
+    ///
+    /// assert_eq!(capture.text(), concat!(
+    ///     "note: 1 entry was fixed since the baseline\n",
+    ///     "note: fixed since the baseline: [W0001] unused key `edition` in `Config.toml`\n"
+    /// ));
+    /// # Ok(()) }
+    /// ```
+    pub fn log_fixed_to_target(&self, target: &Target) -> Result<()> {
+        let fixed = self.fixed();
+        match fixed.len() {
+            0 => return Ok(()),
+            1 => Entry::new_note("1 entry was fixed since the baseline").log_to_target(target)?,
+            count => Entry::new_note(format!("{} entries were fixed since the baseline", count)).log_to_target(target)?
+        }
+        for entry in fixed {
+            let mut message = String::from("fixed since the baseline: ");
+            if let Some(code) = &entry.code {
+                message.push_str(&format!("[{}] ", code));
+            }
+            // Only the first line, as in the summary of a diff.
+            message.push_str(entry.message.lines().next().unwrap_or_default());
+            if let Some(file) = &entry.file {
+                message.push_str(&format!(" in `{}`", file));
+            }
+            Entry::new_note(message).log_to_target(target)?;
+        }
+        Ok(())
+    }
+}
+
+/// Escapes the backslashes, tabs and line breaks of a field of a baseline file.
+fn escape(field: &str) -> String {
+    field.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n").replace('\r', "\\r")
+}
+
+/// Escapes a field which can be missing, so that a literal `-` is not read as missing.
+fn escape_optional(field: &str) -> String {
+    match field {
+        "-" => "\\-".to_owned(),
+        field => escape(field)
+    }
+}
+
+/// Reverts [`escape`] and [`escape_optional`],
+/// or returns `None` if `field` contains an invalid escape sequence.
+fn unescape(field: &str) -> Option<String> {
+    let mut unescaped = String::with_capacity(field.len());
+    let mut chars = field.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            unescaped.push(match chars.next()? {
+                '\\' => '\\',
+                't' => '\t',
+                'n' => '\n',
+                'r' => '\r',
+                '-' => '-',
+                _ => return None
+            });
+        } else {
+            unescaped.push(c);
+        }
+    }
+    Some(unescaped)
+}
@@ -0,0 +1,438 @@
+//! Configuration of the [`PrologueLogger`] before its creation.
+//!
+//! A [`PrologueLoggerBuilder`], obtained through [`PrologueLogger::builder`], collects
+//! the level filter, the color choice, the output format, the sinks (optionally written by
+//! a background thread), the theme and the targets of the logger, and then builds or installs it.
+//!
+//! The same settings can be adjusted by the end users through environment variables,
+//! when the builder is asked to read them with [`from_env`](PrologueLoggerBuilder::from_env):
+//! * [`LOG_VAR`] (`PROLOGUE_LOG`) contains the [filter](Filter::parse) directives, e.g. `info,parser=trace`;
+//! * [`COLOR_VAR`] (`PROLOGUE_COLOR`) contains the [color choice](ColorChoice), i.e.
+//!   `auto`, `always` or `never`;
+//! * [`FORMAT_VAR`] (`PROLOGUE_FORMAT`) contains the [format](Format), i.e. `human`
+//!   or, if the feature `json` is enabled, `json`.
+//!
+//! # Example
+//! ```
+//! # use log::LevelFilter;
+//! # use prologue_logger::PrologueLogger;
+//! # use prologue_logger::builder::ColorChoice;
+//! # use prologue_logger::sink::WriterSink;
+//! # use prologue_logger::theme::{Color, Theme};
+//! fn main() -> prologue_logger::error::Result<()> {
+//!     # let path = std::env::temp_dir().join("prologue-builder-example.log");
+//!     let logger = PrologueLogger::builder()
+//!         .level(LevelFilter::Info)
+//!         .target_level("my-target::network", LevelFilter::Warn)
+//!         .color(ColorChoice::Auto)
+//!         .theme(Theme::new().note(Color::Blue))
+//!         // Write the entries of every target also to a file.
+//!         .sink(WriterSink::new(std::fs::File::create(&path)?))
+//!         .target("my-target")
+//!         .default_target("my-target")
+//!         .build()?;
+//!
+//!     assert!(logger.find_target("my-target").is_some());
+//!     assert!(logger.target_list().route("some-other-target").is_some());
+//!     Ok(())
+//! }
+//! ```
+
+use std::sync::Arc;
+
+use log::LevelFilter;
+
+use crate::{PrologueLogger, FlushGuard, Sinks};
+use crate::baseline::Baseline;
+use crate::error::{ErrorKind, Result};
+use crate::filter::Filter;
+use crate::fingerprint::Deduplicator;
+use crate::lint::LintLevels;
+use crate::sink::{self, Backpressure, BackgroundSink, Sink};
+use crate::theme::Theme;
+
+/// Environment variable containing the filter directives, see [`Filter::parse`].
+pub const LOG_VAR: &str = "PROLOGUE_LOG";
+/// Environment variable containing the color choice, see [`ColorChoice`].
+pub const COLOR_VAR: &str = "PROLOGUE_COLOR";
+/// Environment variable containing the output format, see [`Format`].
+pub const FORMAT_VAR: &str = "PROLOGUE_FORMAT";
+
+/// Whether the entries written to `stderr` are colored.
+///
+/// Colors are available only if the feature `console` is enabled;
+/// otherwise, this setting has no effect.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, Default)]
+pub enum ColorChoice {
+    /// Colors are used if the terminal supports them.
+    #[default]
+    Auto,
+    /// Colors are always used.
+    Always,
+    /// Colors are never used.
+    Never
+}
+impl ColorChoice {
+    /// Parses `auto`, `always` or `never`, ignoring the case.
+    fn parse(value: &str) -> Option<ColorChoice> {
+        match value.to_ascii_lowercase().as_str() {
+            "auto" => Some(ColorChoice::Auto),
+            "always" => Some(ColorChoice::Always),
+            "never" => Some(ColorChoice::Never),
+            _ => None
+        }
+    }
+
+    /// Applies the choice to the whole program.
+    fn apply(self) {
+        #[cfg(feature = "console")]
+        match self {
+            ColorChoice::Auto => (),
+            ColorChoice::Always | ColorChoice::Never => {
+                let enabled = self == ColorChoice::Always;
+                console::set_colors_enabled(enabled);
+                console::set_colors_enabled_stderr(enabled);
+            }
+        }
+    }
+}
+
+/// Format of the entries written to `stderr`.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, Default)]
+pub enum Format {
+    /// The entries are displayed in the style of `cargo`, as with [`StderrSink`](sink::StderrSink).
+    #[default]
+    Human,
+    /// The entries are written as JSON objects, one per line, as with [`JsonSink`](sink::JsonSink).
+    #[cfg(feature = "json")]
+    Json
+}
+impl Format {
+    /// Parses `human` or `json`, ignoring the case.
+    fn parse(value: &str) -> Option<Format> {
+        match value.to_ascii_lowercase().as_str() {
+            "human" => Some(Format::Human),
+            #[cfg(feature = "json")]
+            "json" => Some(Format::Json),
+            _ => None
+        }
+    }
+}
+
+/// Builder of a [`PrologueLogger`].
+///
+/// See the [module documentation](self) for further information.
+#[derive(Clone)]
+pub struct PrologueLoggerBuilder {
+    filter: Filter,
+    color: ColorChoice,
+    format: Format,
+    stderr: bool,
+    sinks: Vec<Arc<dyn Sink>>,
+    background: Option<(usize, Backpressure)>,
+    theme: Option<Theme>,
+    targets: Vec<String>,
+    default_target: Option<String>,
+    deduplicate: bool,
+    error_limit: Option<usize>,
+    lint_levels: Option<LintLevels>,
+    baseline: Option<Baseline>
+}
+impl std::fmt::Debug for PrologueLoggerBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PrologueLoggerBuilder")
+            .field("filter", &self.filter)
+            .field("color", &self.color)
+            .field("format", &self.format)
+            .field("stderr", &self.stderr)
+            .field("sinks", &self.sinks.len())
+            .field("background", &self.background)
+            .field("theme", &self.theme)
+            .field("targets", &self.targets)
+            .field("default_target", &self.default_target)
+            .field("deduplicate", &self.deduplicate)
+            .field("error_limit", &self.error_limit)
+            .field("lint_levels", &self.lint_levels)
+            .field("baseline", &self.baseline.is_some())
+            .finish()
+    }
+}
+impl Default for PrologueLoggerBuilder {
+    fn default() -> Self {
+        PrologueLoggerBuilder {
+            filter: Filter::default(),
+            color: ColorChoice::default(),
+            format: Format::default(),
+            stderr: true,
+            sinks: Vec::new(),
+            background: None,
+            theme: None,
+            targets: Vec::new(),
+            default_target: None,
+            deduplicate: false,
+            error_limit: None,
+            lint_levels: None,
+            baseline: None
+        }
+    }
+}
+impl PrologueLoggerBuilder {
+    /// Creates a new builder with the default settings.
+    ///
+    /// This is equivalent to [`PrologueLogger::builder`].
+    pub fn new() -> PrologueLoggerBuilder {
+        Default::default()
+    }
+
+    /// Sets the level filter of the logger, replacing any previous level or filter.
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Sets the global maximum level of the logged records.
+    pub fn level(mut self, level: LevelFilter) -> Self {
+        self.filter = self.filter.level(level);
+        self
+    }
+
+    /// Sets the maximum level of the records of `target` and its children.
+    pub fn target_level<S: Into<String>>(mut self, target: S, level: LevelFilter) -> Self {
+        self.filter = self.filter.target(target, level);
+        self
+    }
+
+    /// Sets whether the entries written to `stderr` are colored.
+    pub fn color(mut self, color: ColorChoice) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Sets the format of the entries written to `stderr`.
+    pub fn format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Sets whether the targets write their entries to `stderr` (the default).
+    ///
+    /// When the feature `indicatif` is enabled, the human-readable entries are printed
+    /// through the `MultiProgress` of the target list instead.
+    /// Without `stderr` and without other [sinks](PrologueLoggerBuilder::sink), the entries
+    /// are only counted.
+    pub fn stderr(mut self, enabled: bool) -> Self {
+        self.stderr = enabled;
+        self
+    }
+
+    /// Adds a [`Sink`] shared by every target of the logger.
+    pub fn sink<K: Sink + 'static>(mut self, sink: K) -> Self {
+        self.sinks.push(Arc::new(sink));
+        self
+    }
+
+    /// Writes the entries from a dedicated thread, through a [`BackgroundSink`] queueing
+    /// up to `capacity` entries with the given `backpressure` policy.
+    ///
+    /// The entries are written in the same order as they are logged, by all the targets;
+    /// flushing the targets waits for the queued entries to be written. The handle of the
+    /// writer thread is available from the [`FlushGuard`], which joins the thread when dropped.
+    ///
+    /// # Example
+    /// ```
+    /// # fn main() -> prologue_logger::error::Result<()> {
+    /// # use prologue_logger::{Entry, PrologueLogger};
+    /// # use prologue_logger::capture::Capture;
+    /// # use prologue_logger::sink::Backpressure;
+    /// let capture = Capture::new();
+    /// let guard = PrologueLogger::builder()
+    ///     .stderr(false)
+    ///     .sink(capture.clone())
+    ///     .background(1024, Backpressure::DropNewest)
+    ///     .target("my-target")
+    ///     .try_init()?;
+    ///
+    /// Entry::new_warning("written by the background thread")
+    ///     .log("my-target");
+    /// assert_eq!(guard.background_handle().unwrap().dropped(), 0);
+    ///
+    /// // Write the remaining entries and stop the writer thread.
+    /// drop(guard);
+    /// assert_eq!(capture.text(), "warning: written by the background thread\n");
+    /// # Ok(()) }
+    /// ```
+    pub fn background(mut self, capacity: usize, backpressure: Backpressure) -> Self {
+        self.background = Some((capacity, backpressure));
+        self
+    }
+
+    /// Sets the [theme](Theme) to make active when the logger is built.
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = Some(theme);
+        self
+    }
+
+    /// Adds a target to create along with the logger.
+    ///
+    /// Targets added more than once are created only once.
+    pub fn target<S: Into<String>>(mut self, name: S) -> Self {
+        let name = name.into();
+        if !self.targets.contains(&name) {
+            self.targets.push(name);
+        }
+        self
+    }
+
+    /// Sets whether the duplicate entries are hidden, even if logged to different targets;
+    /// see [`TargetList::set_deduplicator`](crate::TargetList::set_deduplicator).
+    pub fn deduplicate(mut self, enabled: bool) -> Self {
+        self.deduplicate = enabled;
+        self
+    }
+
+    /// Sets the maximum number of errors of all the targets together;
+    /// see [`TargetList::set_error_limit`](crate::TargetList::set_error_limit).
+    pub fn error_limit(mut self, limit: usize) -> Self {
+        self.error_limit = Some(limit);
+        self
+    }
+
+    /// Sets the levels of the lints for every target, e.g. parsed from the command line
+    /// with [`LintLevels::from_args`];
+    /// see [`TargetList::set_lint_levels`](crate::TargetList::set_lint_levels).
+    pub fn lint_levels(mut self, levels: LintLevels) -> Self {
+        self.lint_levels = Some(levels);
+        self
+    }
+
+    /// Sets the baseline of the known entries to suppress, e.g. loaded with [`Baseline::load`];
+    /// see [`TargetList::set_baseline`](crate::TargetList::set_baseline).
+    pub fn baseline(mut self, baseline: Baseline) -> Self {
+        self.baseline = Some(baseline);
+        self
+    }
+
+    /// Sets the target receiving the records which do not match any other target,
+    /// see [`TargetList::set_default_target`](crate::TargetList::set_default_target).
+    ///
+    /// The target is created along with the logger, if not added already.
+    pub fn default_target<S: Into<String>>(mut self, name: S) -> Self {
+        let name = name.into();
+        self = self.target(name.clone());
+        self.default_target = Some(name);
+        self
+    }
+
+    /// Overrides the settings with the values of the environment variables
+    /// [`LOG_VAR`], [`COLOR_VAR`] and [`FORMAT_VAR`], if set.
+    ///
+    /// Filter directives read from [`LOG_VAR`] are applied on top of the current filter.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ErrorKind::InvalidFilterDirective`] if [`LOG_VAR`] contains an invalid directive,
+    /// or [`ErrorKind::InvalidEnvironmentVariable`] if [`COLOR_VAR`] or [`FORMAT_VAR`]
+    /// contain an unknown value.
+    ///
+    /// # Example
+    /// ```
+    /// # fn main() -> prologue_logger::error::Result<()> {
+    /// # use prologue_logger::PrologueLogger;
+    /// # use prologue_logger::builder::COLOR_VAR;
+    /// std::env::set_var(COLOR_VAR, "sometimes");
+    /// let err = PrologueLogger::builder().from_env().unwrap_err();
+    /// assert_eq!(err.to_string(), "invalid value `sometimes` for `PROLOGUE_COLOR`");
+    /// # Ok(()) }
+    /// ```
+    pub fn from_env(mut self) -> Result<Self> {
+        if let Ok(directives) = std::env::var(LOG_VAR) {
+            self.filter = self.filter.directives(&directives)?;
+        }
+        if let Ok(value) = std::env::var(COLOR_VAR) {
+            self.color = ColorChoice::parse(&value)
+                .ok_or(ErrorKind::InvalidEnvironmentVariable(COLOR_VAR.to_owned(), value))?;
+        }
+        if let Ok(value) = std::env::var(FORMAT_VAR) {
+            self.format = Format::parse(&value)
+                .ok_or(ErrorKind::InvalidEnvironmentVariable(FORMAT_VAR.to_owned(), value))?;
+        }
+        Ok(self)
+    }
+
+    /// Builds the logger without installing it.
+    ///
+    /// This also applies the color choice and makes the theme active, if set.
+    pub fn build(self) -> Result<PrologueLogger> {
+        self.color.apply();
+        if let Some(theme) = self.theme {
+            theme.set_active();
+        }
+        let mut logger = PrologueLogger::with_filter(self.filter);
+        // The new targets write to these sinks only, even if there are none.
+        let list_sinks = Sinks::default();
+        logger.target_list.sinks = Some(list_sinks.clone());
+        let target_list = &logger.target_list;
+        // With a background writer, the sinks are written by its thread instead of the targets.
+        let sinks = match self.background {
+            Some(_) => Sinks::default(),
+            None => list_sinks.clone()
+        };
+        if self.stderr {
+            match self.format {
+                #[cfg(not(feature = "indicatif"))]
+                Format::Human => sinks.add(sink::StderrSink),
+                #[cfg(feature = "indicatif")]
+                Format::Human => sinks.add(sink::MultiProgressSink::new(target_list.multi_progress.clone())),
+                #[cfg(feature = "json")]
+                Format::Json => sinks.add(sink::JsonSink::new(std::io::stderr()))
+            }
+        }
+        for sink in self.sinks {
+            sinks.add_shared(sink);
+        }
+        let background = self.background.map(|(capacity, backpressure)| {
+            let (sink, handle) = BackgroundSink::new(sinks, capacity, backpressure);
+            list_sinks.add(sink);
+            handle
+        });
+        if self.deduplicate {
+            target_list.set_deduplicator(Some(Deduplicator::new()));
+        }
+        target_list.set_error_limit(self.error_limit);
+        if let Some(levels) = self.lint_levels {
+            target_list.set_lint_levels(levels);
+        }
+        if let Some(baseline) = self.baseline {
+            target_list.set_baseline(Some(baseline));
+        }
+        for name in self.targets {
+            target_list.create_target(name)?;
+        }
+        if let Some(name) = self.default_target {
+            target_list.set_default_target(name);
+        }
+        logger.background = background;
+        Ok(logger)
+    }
+
+    /// Builds the logger and initializes it as the main logger with crate [`log`],
+    /// as [`PrologueLogger::init`] does, and returns a [`FlushGuard`] which flushes
+    /// the targets when dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ErrorKind::SetLoggerError`] if a logger was already initialized.
+    pub fn try_init(self) -> Result<FlushGuard> {
+        self.build()?.install()
+    }
+
+    /// Builds the logger and initializes it as the main logger with crate [`log`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if a logger was already initialized; see [`try_init`](PrologueLoggerBuilder::try_init)
+    /// for a non-panicking version.
+    pub fn init(self) -> FlushGuard {
+        self.try_init().expect("could not initialize the logger")
+    }
+}
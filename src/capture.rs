@@ -0,0 +1,209 @@
+//! In-memory recording of log entries, mainly useful for unit tests.
+//!
+//! A [`Capture`] is a [`Sink`] which is usually attached to a [`Target`](crate::Target) through
+//! [`Target::with_capture`](crate::Target::with_capture); from then on, everything logged
+//! to the target is recorded instead of being printed, both as structured values
+//! and as uncolored text.
+//!
+//! # Example
+//! ```
+//! # fn main() -> prologue_logger::error::Result<()> {
+//! # use prologue_logger::{Entry, Target};
+//! # use prologue_logger::capture::Capture;
+//! # fn validate(target: &Target) -> prologue_logger::error::Result<()> {
+//! #     Entry::new_warning("this is a warning line")
+//! #         .code("W001")
+//! #         .named_source("examples/file.rs", 8, 36)
+//! #         .new_line(8, "    let entry = Entry::new_warning(\"this is a warning line\")")
+//! #         .annotate_note(36, 24, "this is the text")?
+//! #         .finish()
+//! #         .log_to_target(target)
+//! # }
+//! let capture = Capture::new();
+//! let target = Target::with_capture("validator", capture.clone());
+//!
+//! // Run the code under test.
+//! validate(&target)?;
+//!
+//! capture.assert_counts(1, 0);
+//! capture.assert_message("this is a warning line");
+//! capture.assert_code("W001");
+//! capture.assert_warning_at(8, 36);
+//! capture.assert_annotation(8, 36, 24);
+//! # Ok(()) }
+//! ```
+
+use std::sync::{Arc, Mutex};
+
+use crate::{Entry, EntryKind, LogItem};
+use crate::error::Result;
+use crate::sink::Sink;
+
+/// In-memory recording of the entries logged to one or more targets.
+///
+/// Cloning a `Capture` produces another handle to the same recording.
+#[derive(Clone, Debug, Default)]
+pub struct Capture {
+    items: Arc<Mutex<Vec<(LogItem, String)>>>
+}
+impl Sink for Capture {
+    fn write(&self, item: &LogItem) -> Result<()> {
+        self.items.lock().unwrap().push((item.clone(), item.plain_text()));
+        Ok(())
+    }
+
+    fn flush(&self) -> Result<()> {
+        Ok(())
+    }
+}
+impl Capture {
+    /// Creates a new, empty recording.
+    pub fn new() -> Capture {
+        Default::default()
+    }
+
+    /// Returns all the recorded items, in the order in which they were logged.
+    pub fn items(&self) -> Vec<LogItem> {
+        self.items.lock().unwrap().iter()
+            .map(|(item, _)| item.clone())
+            .collect()
+    }
+
+    /// Returns all the recorded entries, including the ones inside a [`MultiEntry`](crate::MultiEntry).
+    pub fn entries(&self) -> Vec<Entry> {
+        let mut entries = Vec::new();
+        for (item, _) in self.items.lock().unwrap().iter() {
+            match item {
+                LogItem::Entry(entry) => entries.push(entry.clone()),
+                LogItem::MultiEntry(multi) => entries.extend(multi.entries.iter().cloned()),
+                LogItem::Task(_) => {}
+            }
+        }
+        entries
+    }
+
+    /// Returns the uncolored text of all the recorded items, as it would have been printed.
+    pub fn text(&self) -> String {
+        self.items.lock().unwrap().iter()
+            .map(|(_, text)| text.as_str())
+            .collect()
+    }
+
+    /// Removes all the recorded items.
+    pub fn clear(&self) {
+        self.items.lock().unwrap().clear();
+    }
+
+    /// Returns the number of recorded warnings, counted in the same way as
+    /// [`Target::warning_count`](crate::Target::warning_count).
+    pub fn warning_count(&self) -> usize {
+        self.count(EntryKind::Warning)
+    }
+
+    /// Returns the number of recorded errors, counted in the same way as
+    /// [`Target::error_count`](crate::Target::error_count).
+    pub fn error_count(&self) -> usize {
+        self.count(EntryKind::Error)
+    }
+
+    fn count(&self, kind: EntryKind) -> usize {
+        self.items.lock().unwrap().iter()
+            .filter(|(item, _)| item.kind() == Some(kind))
+            .count()
+    }
+
+    /// Asserts that exactly `warnings` warnings and `errors` errors were recorded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the counts differ, printing the recorded text.
+    #[track_caller]
+    pub fn assert_counts(&self, warnings: usize, errors: usize) {
+        let (actual_warnings, actual_errors) = (self.warning_count(), self.error_count());
+        if (actual_warnings, actual_errors) != (warnings, errors) {
+            self.fail(format!("expected {} warning(s) and {} error(s), found {} warning(s) and {} error(s)",
+                              warnings, errors, actual_warnings, actual_errors));
+        }
+    }
+
+    /// Asserts that at least one recorded entry has the given `message`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no entry has the given message, printing the recorded text.
+    #[track_caller]
+    pub fn assert_message<S: AsRef<str>>(&self, message: S) {
+        let message = message.as_ref();
+        if !self.entries().iter().any(|e| e.text == message) {
+            self.fail(format!("no entry with message `{}`", message));
+        }
+    }
+
+    /// Asserts that at least one recorded entry has the given [`code`](Entry::code).
+    ///
+    /// # Panics
+    ///
+    /// Panics if no entry has the given code, printing the recorded text.
+    #[track_caller]
+    pub fn assert_code<S: AsRef<str>>(&self, code: S) {
+        let code = code.as_ref();
+        if !self.entries().iter().any(|e| e.code.as_deref() == Some(code)) {
+            self.fail(format!("no entry with code `{}`", code));
+        }
+    }
+
+    /// Asserts that at least one warning refers to the given `line` and `position`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no warning refers to the given location, printing the recorded text.
+    #[track_caller]
+    pub fn assert_warning_at(&self, line: usize, position: usize) {
+        self.assert_at(EntryKind::Warning, line, position);
+    }
+
+    /// Asserts that at least one error refers to the given `line` and `position`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no error refers to the given location, printing the recorded text.
+    #[track_caller]
+    pub fn assert_error_at(&self, line: usize, position: usize) {
+        self.assert_at(EntryKind::Error, line, position);
+    }
+
+    #[track_caller]
+    fn assert_at(&self, kind: EntryKind, line: usize, position: usize) {
+        let found = self.entries().iter()
+            .filter(|e| e.kind == kind)
+            .filter_map(|e| e.source.as_ref())
+            .any(|s| s.line_number == line && s.position == position);
+        if !found {
+            self.fail(format!("no {} at {}:{}", kind.label(), line, position));
+        }
+    }
+
+    /// Asserts that at least one entry underlines `len` characters starting from
+    /// `position` on the source line `line`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no such annotation exists, printing the recorded text.
+    #[track_caller]
+    pub fn assert_annotation(&self, line: usize, position: usize, len: usize) {
+        let found = self.entries().iter()
+            .filter_map(|e| e.source.as_ref())
+            .flat_map(|s| s.lines.iter())
+            .filter(|l| l.line == line)
+            .flat_map(|l| l.annotations.iter())
+            .any(|a| a.reference.position == position && a.reference.len == len);
+        if !found {
+            self.fail(format!("no annotation of length {} at {}:{}", len, line, position));
+        }
+    }
+
+    #[track_caller]
+    fn fail(&self, message: String) -> ! {
+        panic!("{}\ncaptured output:\n{}", message, self.text())
+    }
+}
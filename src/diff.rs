@@ -0,0 +1,288 @@
+//! Comparison of the entries of two runs, e.g. to report only the entries introduced by a change.
+//!
+//! A [`Run`] stores the entries logged to each target, and is serialized as a JSON object
+//! mapping the name of every target to the array of its entries, in the format of
+//! [`JsonSink`](crate::sink::JsonSink). [`RunDiff`] compares two runs target by target,
+//! matching the entries by their [line-independent fingerprint](Fingerprint::of_item_ignoring_lines),
+//! so that an entry is unchanged even if the code above it moved.
+//! A run can also be read back from the lines written by a [`JsonSink`](crate::sink::JsonSink),
+//! e.g. by a logger [built](crate::builder) with the JSON format, with [`Run::from_jsonl`].
+//!
+//! The [`Display`] implementation of [`RunDiff`] produces a human summary, while
+//! [`to_json`](RunDiff::to_json) produces the JSON report.
+//!
+//! # Example
+//! ```
+//! # fn main() -> prologue_logger::error::Result<()> {
+//! # use prologue_logger::{Entry, LogItem};
+//! # use prologue_logger::diff::{Run, RunDiff};
+//! let unused_key = |line| LogItem::from(Entry::new_warning("unused key `edition`")
+//!     .named_source("Config.toml", line, 1)
+//!     .finish());
+//! let invalid_value = LogItem::from(Entry::new_error("invalid value `42`"));
+//! let deprecated_key = LogItem::from(Entry::new_warning("the key `authors` is deprecated"));
+//!
+//! let before = Run::new().target("my-target", [unused_key(3), invalid_value]);
+//! // The runs are usually stored in files by the CI.
+//! let before = Run::parse(&before.to_json()?)?;
+//! let after = Run::new().target("my-target", [unused_key(5), deprecated_key.clone()]);
+//!
+//! let diff = RunDiff::new(&before, &after);
+//! assert_eq!(diff.targets()[0].new_items(), &[deprecated_key]);
+//! assert_eq!(diff.to_string(), concat!(
+//!     "`my-target`: 1 new, 1 resolved, 1 unchanged\n",
+//!     "  new: warning: the key `authors` is deprecated\n",
+//!     "  resolved: error: invalid value `42`\n"
+//! ));
+//! # Ok(()) }
+//! ```
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+
+use crate::LogItem;
+use crate::error::{ErrorKind, Result};
+use crate::fingerprint::Fingerprint;
+
+/// The entries logged to each target during a run.
+#[derive(Clone, Eq, PartialEq, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
+pub struct Run {
+    targets: BTreeMap<String, Vec<LogItem>>
+}
+impl Run {
+    /// Creates an empty run.
+    pub fn new() -> Run {
+        Default::default()
+    }
+
+    /// Adds the `items` logged to the target called `name`, e.g. the items of its
+    /// [`Capture`](crate::capture::Capture).
+    pub fn target<S: Into<String>, I: IntoIterator<Item = LogItem>>(mut self, name: S, items: I) -> Self {
+        self.targets.entry(name.into()).or_default().extend(items);
+        self
+    }
+
+    /// Returns the names of the targets of the run and their items, sorted by name.
+    pub fn targets(&self) -> impl Iterator<Item = (&str, &[LogItem])> {
+        self.targets.iter().map(|(name, items)| (name.as_str(), items.as_slice()))
+    }
+
+    /// Parses a run serialized with [`to_json`](Run::to_json).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ErrorKind::InvalidRun`] if `json` is not a valid run.
+    pub fn parse(json: &str) -> Result<Run> {
+        serde_json::from_str(json).map_err(|err| ErrorKind::InvalidRun(err.to_string()).into())
+    }
+
+    /// Parses the lines written by a [`JsonSink`](crate::sink::JsonSink), grouping the entries
+    /// by the target they were logged to.
+    ///
+    /// Empty lines and entries without a target, e.g. the warnings about the log records
+    /// which do not match any target, are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ErrorKind::InvalidRun`] if a line is not a valid entry.
+    ///
+    /// # Example
+    /// ```
+    /// # fn main() -> prologue_logger::error::Result<()> {
+    /// # use prologue_logger::{Entry, Target};
+    /// # use prologue_logger::diff::Run;
+    /// # use prologue_logger::sink::JsonSink;
+    /// # let path = std::env::temp_dir().join("prologue-run-example.jsonl");
+    /// let file = std::fs::File::create(&path)?;
+    /// let target = Target::with_sink("my-target", JsonSink::new(file));
+    /// Entry::new_warning("unused key `edition`")
+    ///     .log_to_target(&target)?;
+    /// target.flush()?;
+    ///
+    /// let run = Run::from_jsonl(&std::fs::read_to_string(&path)?)?;
+    /// let expected = Run::new().target("my-target", [Entry::new_warning("unused key `edition`").into()]);
+    /// assert_eq!(run, expected);
+    /// # Ok(()) }
+    /// ```
+    pub fn from_jsonl(lines: &str) -> Result<Run> {
+        let mut run = Run::new();
+        for (index, line) in lines.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+            let line: JsonLine = serde_json::from_str(line)
+                .map_err(|err| ErrorKind::InvalidRun(format!("line {}: {}", index + 1, err)))?;
+            if let Some(target) = line.target {
+                run.targets.entry(target).or_default().push(line.item);
+            }
+        }
+        Ok(run)
+    }
+
+    /// Loads a run saved with [`save`](Run::save).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ErrorKind::IoError`] if the file cannot be read,
+    /// or [`ErrorKind::InvalidRun`] if it cannot be parsed.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Run> {
+        Run::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Serializes the run to JSON.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string(self).map_err(|err| std::io::Error::from(err).into())
+    }
+
+    /// Writes the run to a file as JSON, replacing its contents.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        std::fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+}
+
+/// Line written by a [`JsonSink`](crate::sink::JsonSink).
+#[derive(serde::Deserialize)]
+struct JsonLine {
+    target: Option<String>,
+    #[serde(flatten)]
+    item: LogItem
+}
+
+/// Comparison of the entries of a target in two runs.
+///
+/// Tasks are ignored.
+#[derive(Clone, Eq, PartialEq, Debug, serde::Serialize)]
+pub struct TargetDiff {
+    name: String,
+    new: Vec<LogItem>,
+    resolved: Vec<LogItem>,
+    unchanged: Vec<LogItem>
+}
+impl TargetDiff {
+    fn compare(name: &str, before: &[LogItem], after: &[LogItem]) -> TargetDiff {
+        let mut remaining = HashMap::<_, VecDeque<_>>::new();
+        for (index, item) in before.iter().enumerate() {
+            if let Some(fingerprint) = Fingerprint::of_item_ignoring_lines(item) {
+                remaining.entry(fingerprint).or_default().push_back(index);
+            }
+        }
+        let mut diff = TargetDiff { name: name.to_owned(), new: Vec::new(), resolved: Vec::new(), unchanged: Vec::new() };
+        for item in after {
+            if let Some(fingerprint) = Fingerprint::of_item_ignoring_lines(item) {
+                match remaining.get_mut(&fingerprint).and_then(VecDeque::pop_front) {
+                    Some(_) => diff.unchanged.push(item.clone()),
+                    None => diff.new.push(item.clone())
+                }
+            }
+        }
+        let mut resolved = remaining.into_values().flatten().collect::<Vec<_>>();
+        resolved.sort_unstable();
+        diff.resolved = resolved.into_iter().map(|index| before[index].clone()).collect();
+        diff
+    }
+
+    /// Returns the name of the target.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the entries of the second run which do not appear in the first one.
+    pub fn new_items(&self) -> &[LogItem] {
+        &self.new
+    }
+
+    /// Returns the entries of the first run which do not appear in the second one.
+    pub fn resolved(&self) -> &[LogItem] {
+        &self.resolved
+    }
+
+    /// Returns the entries of the second run which also appear in the first one.
+    pub fn unchanged(&self) -> &[LogItem] {
+        &self.unchanged
+    }
+}
+
+/// Comparison of the entries of two runs, target by target; see the [module documentation](self).
+#[derive(Clone, Eq, PartialEq, Debug, serde::Serialize)]
+pub struct RunDiff {
+    targets: Vec<TargetDiff>
+}
+impl Display for RunDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for target in self.targets.iter() {
+            writeln!(
+                f, "`{}`: {} new, {} resolved, {} unchanged",
+                target.name, target.new.len(), target.resolved.len(), target.unchanged.len()
+            )?;
+            for item in target.new.iter() {
+                writeln!(f, "  new: {}", summary(item))?;
+            }
+            for item in target.resolved.iter() {
+                writeln!(f, "  resolved: {}", summary(item))?;
+            }
+        }
+        Ok(())
+    }
+}
+impl RunDiff {
+    /// Compares the entries of the run `before` with the ones of the run `after`.
+    ///
+    /// Every target of either run is compared, in the order of their names.
+    /// An entry of `before` matches at most one entry of `after`.
+    pub fn new(before: &Run, after: &Run) -> RunDiff {
+        let mut names = before.targets.keys().chain(after.targets.keys()).collect::<Vec<_>>();
+        names.sort_unstable();
+        names.dedup();
+        let targets = names.into_iter()
+            .map(|name| TargetDiff::compare(
+                name,
+                before.targets.get(name).map(Vec::as_slice).unwrap_or_default(),
+                after.targets.get(name).map(Vec::as_slice).unwrap_or_default()
+            ))
+            .collect();
+        RunDiff { targets }
+    }
+
+    /// Returns the comparison of every target.
+    pub fn targets(&self) -> &[TargetDiff] {
+        &self.targets
+    }
+
+    /// Returns the entries of the second run which do not appear in the first one, for all the targets.
+    pub fn new_items(&self) -> impl Iterator<Item = &LogItem> {
+        self.targets.iter().flat_map(|target| target.new.iter())
+    }
+
+    /// Serializes the comparison to JSON, as an object whose `targets` array contains
+    /// the `name` and the `new`, `resolved` and `unchanged` entries of every target.
+    ///
+    /// # Example
+    /// ```
+    /// # fn main() -> prologue_logger::error::Result<()> {
+    /// # use prologue_logger::{Entry, LogItem};
+    /// # use prologue_logger::diff::{Run, RunDiff};
+    /// let after = Run::new().target("my-target", [LogItem::from(Entry::new_warning("unused key `edition`"))]);
+    ///
+    /// assert_eq!(RunDiff::new(&Run::new(), &after).to_json()?, concat!(
+    ///     r#"{"targets":[{"name":"my-target","#,
+    ///     r#""new":[{"type":"entry","kind":"warning","bright":false,"message":"unused key `edition`"}],"#,
+    ///     r#""resolved":[],"unchanged":[]}]}"#
+    /// ));
+    /// # Ok(()) }
+    /// ```
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string(self).map_err(|err| std::io::Error::from(err).into())
+    }
+}
+
+/// Returns the first line of an item, followed by its location if any.
+fn summary(item: &LogItem) -> String {
+    let text = item.plain_text();
+    let mut summary = text.lines().next().unwrap_or_default().to_owned();
+    if let Some(entry) = item.primary_entry() {
+        if let (Some(file), Some((line, position))) = (entry.file(), entry.location()) {
+            summary.push_str(&format!(" ({}:{}:{})", file.display(), line, position));
+        }
+    }
+    summary
+}
@@ -43,7 +43,7 @@ impl From<super::MultiEntry> for PartialConfiguration {
 #[derive(Debug)]
 pub struct Error {
     kind: ErrorKind,
-    partial_configuration: PartialConfiguration
+    partial_configuration: Box<PartialConfiguration>
 }
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
@@ -52,19 +52,18 @@ impl std::fmt::Display for Error {
 }
 impl From<ErrorKind> for Error {
     fn from(kind: ErrorKind) -> Self {
-        Error { kind, partial_configuration: PartialConfiguration::None }
+        Error { kind, partial_configuration: Box::new(PartialConfiguration::None) }
     }
 }
 #[cfg(feature = "log")]
 impl From<log::SetLoggerError> for Error {
     fn from(err: log::SetLoggerError) -> Self {
-        Error { kind: ErrorKind::SetLoggerError(err), partial_configuration: PartialConfiguration::None }
+        Error { kind: ErrorKind::SetLoggerError(err), partial_configuration: Box::new(PartialConfiguration::None) }
     }
 }
-#[cfg(feature = "indicatif")]
 impl From<std::io::Error> for Error {
     fn from(err: std::io::Error) -> Self {
-        Error { kind: ErrorKind::IoError(Box::new(err)), partial_configuration: PartialConfiguration::None }
+        Error { kind: ErrorKind::IoError(Box::new(err)), partial_configuration: Box::new(PartialConfiguration::None) }
     }
 }
 impl std::error::Error for Error {}
@@ -76,13 +75,13 @@ impl Error {
 
     /// Attaches a partial configuration to this `struct`.
     pub(crate) fn set_partial_configuration<P: Into<PartialConfiguration>>(&mut self, cfg: P) {
-        self.partial_configuration = cfg.into();
+        *self.partial_configuration = cfg.into();
     }
 
     /// Discards the error and returns the inner partial configuration.
     pub fn into_partial_configuration(self) -> PartialConfiguration {
         let Error { partial_configuration: partial, .. } = self;
-        partial
+        *partial
     }
 }
 
@@ -100,8 +99,39 @@ pub enum ErrorKind {
     /// For further information, see crate [`log`](https://docs.rs/log/0.4.17/log/index.html).
     #[cfg(feature = "log")]
     SetLoggerError(log::SetLoggerError),
-    /// Generic IO error.
-    #[cfg(feature = "indicatif")]
+    /// A level filter directive could not be parsed.
+    ///
+    /// For further information, see [`Filter::parse`](crate::filter::Filter::parse).
+    #[cfg(feature = "log")]
+    InvalidFilterDirective(String),
+    /// An environment variable read by the logger contains an invalid value.
+    ///
+    /// The first field is the name of the variable, the second one is its value.
+    /// For further information, see [`PrologueLoggerBuilder::from_env`](crate::builder::PrologueLoggerBuilder::from_env).
+    #[cfg(feature = "log")]
+    InvalidEnvironmentVariable(String, String),
+    /// A command line argument is not a valid lint flag, e.g. `-D warnings`.
+    ///
+    /// For further information, see [`LintLevels::from_args`](crate::lint::LintLevels::from_args).
+    InvalidLintFlag(String),
+    /// A lint is not registered, or a lint level refers to an unknown lint or group.
+    ///
+    /// For further information, see [`LintRegistry`](crate::lint::LintRegistry).
+    UnknownLint(String),
+    /// A fingerprint is not made of 16 hexadecimal digits.
+    ///
+    /// For further information, see [`Fingerprint`](crate::fingerprint::Fingerprint).
+    InvalidFingerprint(String),
+    /// A line of a baseline file could not be parsed; the field is the line number.
+    ///
+    /// For further information, see [`Baseline::parse`](crate::baseline::Baseline::parse).
+    InvalidBaseline(usize),
+    /// A serialized run could not be parsed; the field describes the error.
+    ///
+    /// For further information, see [`Run::parse`](crate::diff::Run::parse).
+    #[cfg(feature = "json")]
+    InvalidRun(String),
+    /// Generic IO error, e.g. while writing to a [`Sink`](crate::sink::Sink).
     IoError(Box<std::io::Error>)
 }
 impl std::fmt::Display for ErrorKind {
@@ -112,7 +142,16 @@ impl std::fmt::Display for ErrorKind {
             ErrorKind::OverlappingAnnotation => write!(f, "annotation overlaps with previous annotation"),
             #[cfg(feature = "log")]
             ErrorKind::SetLoggerError(err) => (err as &dyn std::fmt::Display).fmt(f),
-            #[cfg(feature = "indicatif")]
+            #[cfg(feature = "log")]
+            ErrorKind::InvalidFilterDirective(directive) => write!(f, "invalid filter directive `{}`", directive),
+            #[cfg(feature = "log")]
+            ErrorKind::InvalidEnvironmentVariable(var, value) => write!(f, "invalid value `{}` for `{}`", value, var),
+            ErrorKind::InvalidLintFlag(flag) => write!(f, "invalid lint flag `{}`", flag),
+            ErrorKind::UnknownLint(name) => write!(f, "unknown lint: `{}`", name),
+            ErrorKind::InvalidFingerprint(text) => write!(f, "invalid fingerprint `{}`", text),
+            ErrorKind::InvalidBaseline(line) => write!(f, "invalid baseline entry on line {}", line),
+            #[cfg(feature = "json")]
+            ErrorKind::InvalidRun(err) => write!(f, "invalid run: {}", err),
             ErrorKind::IoError(err) => (err as &dyn std::fmt::Display).fmt(f)
         }
     }
@@ -120,7 +159,7 @@ impl std::fmt::Display for ErrorKind {
 impl ErrorKind {
     /// Promotes this `ErrorKind` to an error with the given partial configuration.
     pub(crate) fn into_error_with_partial_configuration<P: Into<PartialConfiguration>>(self, cfg: P) -> Error {
-        let partial = cfg.into();
+        let partial = Box::new(cfg.into());
         Error { kind: self, partial_configuration: partial }
     }
 }
@@ -0,0 +1,177 @@
+//! Level filtering for the [`PrologueLogger`](crate::PrologueLogger).
+//!
+//! A [`Filter`] contains a global maximum level and any number of per-target maximum levels.
+//! It can be built programmatically or parsed from `RUST_LOG`-style directives,
+//! i.e. a comma-separated list of `target=level`, `level` (the global level) or `target`
+//! (which enables every level for the target).
+//!
+//! Per-target levels also apply to the children of the target, where `a::b` is a child of `a`;
+//! the most specific directive wins.
+//!
+//! # Example
+//! ```
+//! # fn main() -> prologue_logger::error::Result<()> {
+//! # use log::{Level, LevelFilter};
+//! # use prologue_logger::filter::Filter;
+//! let filter: Filter = "info,parser=trace,network=off".parse()?;
+//!
+//! assert_eq!(filter.max_level(), LevelFilter::Trace);
+//! assert!(filter.enabled("parser::lexer", Level::Debug));
+//! assert!(!filter.enabled("other", Level::Debug));
+//! assert!(!filter.enabled("network", Level::Error));
+//! # Ok(()) }
+//! ```
+
+use std::str::FromStr;
+
+use log::{Level, LevelFilter};
+
+use crate::error::{Error, ErrorKind, Result};
+
+/// Maximum levels of the records which are logged, globally and per target.
+///
+/// The default filter allows every level up to [`Debug`](LevelFilter::Debug),
+/// which is the level used by [`Entry::log`](crate::Entry::log) for help entries.
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+pub struct Filter {
+    level: LevelFilter,
+    targets: Vec<(String, LevelFilter)>
+}
+impl Default for Filter {
+    fn default() -> Self {
+        Filter { level: LevelFilter::Debug, targets: Vec::new() }
+    }
+}
+impl FromStr for Filter {
+    type Err = Error;
+
+    fn from_str(directives: &str) -> Result<Filter> {
+        Filter::parse(directives)
+    }
+}
+impl Filter {
+    /// Creates the default filter.
+    pub fn new() -> Filter {
+        Default::default()
+    }
+
+    /// Parses a comma-separated list of `RUST_LOG`-style directives.
+    ///
+    /// Each directive can be `level`, which sets the global level, `target=level`,
+    /// which sets the level of a target, or `target`, which enables every level for a target.
+    /// Levels are case-insensitive and can be any of `off`, `error`, `warn`, `info`,
+    /// `debug` and `trace`. Empty directives are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ErrorKind::InvalidFilterDirective`] if some directive contains an unknown level.
+    ///
+    /// # Example
+    /// ```
+    /// # fn main() -> prologue_logger::error::Result<()> {
+    /// # use log::LevelFilter;
+    /// # use prologue_logger::filter::Filter;
+    /// let filter = Filter::parse("mytarget=warn,other=trace")?;
+    /// assert_eq!(filter, Filter::new()
+    ///     .target("mytarget", LevelFilter::Warn)
+    ///     .target("other", LevelFilter::Trace));
+    ///
+    /// assert!(Filter::parse("mytarget=loud").is_err());
+    /// # Ok(()) }
+    /// ```
+    pub fn parse(directives: &str) -> Result<Filter> {
+        Filter::new().directives(directives)
+    }
+
+    /// Applies a comma-separated list of directives on top of this filter,
+    /// with the same syntax as [`parse`](Filter::parse).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ErrorKind::InvalidFilterDirective`] if some directive contains an unknown level.
+    ///
+    /// # Example
+    /// ```
+    /// # fn main() -> prologue_logger::error::Result<()> {
+    /// # use log::LevelFilter;
+    /// # use prologue_logger::filter::Filter;
+    /// let filter = Filter::new()
+    ///     .level(LevelFilter::Warn)
+    ///     .directives("parser=trace")?;
+    /// assert_eq!(filter.target_level("parser"), LevelFilter::Trace);
+    /// assert_eq!(filter.target_level("other"), LevelFilter::Warn);
+    /// # Ok(()) }
+    /// ```
+    pub fn directives(self, directives: &str) -> Result<Filter> {
+        let mut filter = self;
+        for directive in directives.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            let invalid = || -> Error { ErrorKind::InvalidFilterDirective(directive.to_owned()).into() };
+            match directive.split_once('=') {
+                Some((target, level)) => {
+                    let level = LevelFilter::from_str(level.trim()).map_err(|_| invalid())?;
+                    filter = filter.target(target.trim(), level);
+                },
+                None => match LevelFilter::from_str(directive) {
+                    Ok(level) => filter = filter.level(level),
+                    Err(_) if directive.chars().all(|c| c.is_alphanumeric() || "_-:.".contains(c)) =>
+                        filter = filter.target(directive, LevelFilter::Trace),
+                    Err(_) => return Err(invalid())
+                }
+            }
+        }
+        Ok(filter)
+    }
+
+    /// Parses the directives contained in the environment variable `var`.
+    ///
+    /// If the variable is not set, the default filter is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ErrorKind::InvalidFilterDirective`] if some directive contains an unknown level.
+    pub fn from_env(var: &str) -> Result<Filter> {
+        match std::env::var(var) {
+            Ok(directives) => Filter::parse(&directives),
+            Err(_) => Ok(Filter::new())
+        }
+    }
+
+    /// Sets the global maximum level, used by the targets without a specific level.
+    pub fn level(mut self, level: LevelFilter) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Sets the maximum level of the `target` and its children, replacing any previous level.
+    pub fn target<S: Into<String>>(mut self, target: S, level: LevelFilter) -> Self {
+        let target = target.into();
+        self.targets.retain(|(name, _)| *name != target);
+        self.targets.push((target, level));
+        self
+    }
+
+    /// Returns the maximum level of the given `target`.
+    pub fn target_level(&self, target: &str) -> LevelFilter {
+        self.targets.iter()
+            .filter(|(name, _)| target == name || target.strip_prefix(name.as_str())
+                .map(|rest| rest.starts_with("::"))
+                .unwrap_or(false))
+            .max_by_key(|(name, _)| name.len())
+            .map(|(_, level)| *level)
+            .unwrap_or(self.level)
+    }
+
+    /// Returns `true` if a record with the given `target` and `level` is logged.
+    pub fn enabled(&self, target: &str, level: Level) -> bool {
+        level <= self.target_level(target)
+    }
+
+    /// Returns the most verbose level allowed by the filter, globally or for any target.
+    ///
+    /// This is the level passed to [`log::set_max_level`] when the logger is initialized.
+    pub fn max_level(&self) -> LevelFilter {
+        self.targets.iter()
+            .map(|(_, level)| *level)
+            .fold(self.level, Ord::max)
+    }
+}
@@ -0,0 +1,245 @@
+//! Identification of duplicate log entries.
+//!
+//! A [`Fingerprint`] identifies an entry by its kind, code, message and spans, i.e. the file,
+//! the `-->` location and the position of the annotations, ignoring the labels and the notes.
+//! Fingerprints are stable across runs and platforms, therefore they can be stored.
+//! Line-independent fingerprints (see [`Fingerprint::of_entry_ignoring_lines`]) also survive
+//! the code moving up or down in its file, e.g. to match the entries of a [baseline](crate::baseline).
+//!
+//! A [`Deduplicator`] remembers the fingerprints of the entries logged so far and hides
+//! the duplicates; it can be assigned to a single [`Target`](crate::Target) or shared by
+//! multiple targets, e.g. all the targets of a [`TargetList`](crate::TargetList).
+//!
+//! # Example
+//! ```
+//! # fn main() -> prologue_logger::error::Result<()> {
+//! # use prologue_logger::{Entry, Target};
+//! # use prologue_logger::capture::Capture;
+//! # use prologue_logger::fingerprint::Deduplicator;
+//! let capture = Capture::new();
+//! let target = Target::with_capture("my-target", capture.clone());
+//! target.set_deduplicator(Some(Deduplicator::new()));
+//!
+//! for _ in 0..3 {
+//!     // The same snippet is included by many files.
+//!     Entry::new_warning("unused key `edition`")
+//!         .named_source("shared/Config.toml", 3, 1)
+//!         .finish()
+//!         .log_to_target(&target)?;
+//! }
+//!
+//! assert_eq!(target.warning_count(), 1);
+//! assert_eq!(target.hidden_count(), 2);
+//! target.log_hidden_summary()?;
+//! assert!(capture.text().ends_with("note: 2 duplicate entries were hidden\n"));
+//! # Ok(()) }
+//! ```
+
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+use crate::{Entry, LogItem};
+use crate::error::{Error, ErrorKind, Result};
+
+/// Stable hash of the kind, code, message and spans of an entry.
+///
+/// The [`Display`] implementation produces 16 hexadecimal digits, which the [`FromStr`]
+/// implementation parses back.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Hash)]
+pub struct Fingerprint(u64);
+impl Display for Fingerprint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+impl FromStr for Fingerprint {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Fingerprint> {
+        match u64::from_str_radix(text, 16) {
+            Ok(value) if text.len() == 16 => Ok(Fingerprint(value)),
+            _ => Err(ErrorKind::InvalidFingerprint(text.to_owned()).into())
+        }
+    }
+}
+impl Fingerprint {
+    /// Computes the fingerprint of an entry.
+    ///
+    /// # Example
+    /// ```
+    /// # use prologue_logger::Entry;
+    /// # use prologue_logger::fingerprint::Fingerprint;
+    /// let first = Entry::new_warning("unused variable: `x`").named_source("src/main.rs", 2, 9).finish();
+    /// let second = Entry::new_warning("unused variable: `x`").named_source("src/main.rs", 2, 9)
+    ///     .help("if this is intentional, prefix it with an underscore")
+    ///     .finish();
+    /// let third = Entry::new_warning("unused variable: `x`").named_source("src/main.rs", 7, 9).finish();
+    ///
+    /// // Notes do not contribute to the fingerprint, while locations do.
+    /// assert_eq!(Fingerprint::of_entry(&first), Fingerprint::of_entry(&second));
+    /// assert_ne!(Fingerprint::of_entry(&first), Fingerprint::of_entry(&third));
+    /// ```
+    pub fn of_entry(entry: &Entry) -> Fingerprint {
+        let mut hasher = Fnv::new();
+        hasher.entry(entry);
+        Fingerprint(hasher.0)
+    }
+
+    /// Computes the fingerprint of an item, or returns `None` for a [`Task`](crate::Task).
+    ///
+    /// The fingerprint of a [`MultiEntry`](crate::MultiEntry) covers all its entries.
+    pub fn of_item(item: &LogItem) -> Option<Fingerprint> {
+        match item {
+            LogItem::Entry(entry) => Some(Fingerprint::of_entry(entry)),
+            LogItem::MultiEntry(multi) => {
+                let mut hasher = Fnv::new();
+                for entry in multi.entries() {
+                    hasher.entry(entry);
+                }
+                Some(Fingerprint(hasher.0))
+            },
+            LogItem::Task(_) => None
+        }
+    }
+
+    /// Computes the line-independent fingerprint of an entry, which only covers its file, code,
+    /// message and the contents and columns of its annotated lines, ignoring the line numbers
+    /// and the kind of the entry.
+    ///
+    /// # Example
+    /// ```
+    /// # fn main() -> prologue_logger::error::Result<()> {
+    /// # use prologue_logger::Entry;
+    /// # use prologue_logger::fingerprint::Fingerprint;
+    /// let before = Entry::new_warning("unused key `edition`").named_source("Config.toml", 3, 1)
+    ///     .new_line(3, "edition = 2021")
+    ///     .annotate_warn(1, 7, "")?
+    ///     .finish();
+    /// // Two lines were added above the key, which is now denied.
+    /// let after = Entry::new_error("unused key `edition`").named_source("Config.toml", 5, 1)
+    ///     .new_line(5, "edition = 2021")
+    ///     .annotate_err(1, 7, "")?
+    ///     .finish();
+    ///
+    /// assert_ne!(Fingerprint::of_entry(&before), Fingerprint::of_entry(&after));
+    /// assert_eq!(Fingerprint::of_entry_ignoring_lines(&before), Fingerprint::of_entry_ignoring_lines(&after));
+    /// # Ok(()) }
+    /// ```
+    pub fn of_entry_ignoring_lines(entry: &Entry) -> Fingerprint {
+        let mut hasher = Fnv::new();
+        hasher.entry_ignoring_lines(entry);
+        Fingerprint(hasher.0)
+    }
+
+    /// Computes the line-independent fingerprint of an item, or returns `None` for a [`Task`](crate::Task);
+    /// see [`of_entry_ignoring_lines`](Fingerprint::of_entry_ignoring_lines).
+    ///
+    /// The fingerprint of a [`MultiEntry`](crate::MultiEntry) covers all its entries.
+    pub fn of_item_ignoring_lines(item: &LogItem) -> Option<Fingerprint> {
+        match item {
+            LogItem::Entry(entry) => Some(Fingerprint::of_entry_ignoring_lines(entry)),
+            LogItem::MultiEntry(multi) => {
+                let mut hasher = Fnv::new();
+                for entry in multi.entries() {
+                    hasher.entry_ignoring_lines(entry);
+                }
+                Some(Fingerprint(hasher.0))
+            },
+            LogItem::Task(_) => None
+        }
+    }
+
+    /// Returns the value of the fingerprint.
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+}
+
+/// 64-bit FNV-1a hash, whose output does not depend on the platform or on the Rust version.
+struct Fnv(u64);
+impl Fnv {
+    fn new() -> Fnv {
+        Fnv(0xcbf29ce484222325)
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    fn number(&mut self, number: usize) {
+        self.bytes(&(number as u64).to_le_bytes());
+    }
+
+    fn text(&mut self, text: &str) {
+        self.number(text.len());
+        self.bytes(text.as_bytes());
+    }
+
+    fn entry(&mut self, entry: &Entry) {
+        self.text(&entry.kind().to_string());
+        self.text(entry.error_code().unwrap_or_default());
+        self.text(entry.message());
+        let file = entry.file().map(|file| file.to_string_lossy());
+        self.text(file.as_deref().unwrap_or_default());
+        let (line, column) = entry.location().unwrap_or_default();
+        self.number(line);
+        self.number(column);
+        for line in entry.lines() {
+            self.number(line.line());
+            for annotation in line.annotations() {
+                self.text(&annotation.style().to_string());
+                self.number(annotation.position());
+                self.number(annotation.length());
+            }
+        }
+    }
+
+    fn entry_ignoring_lines(&mut self, entry: &Entry) {
+        self.text(entry.error_code().unwrap_or_default());
+        self.text(entry.message());
+        let file = entry.file().map(|file| file.to_string_lossy());
+        self.text(file.as_deref().unwrap_or_default());
+        for line in entry.lines().iter().filter(|line| !line.annotations().is_empty()) {
+            self.text(line.contents());
+            for annotation in line.annotations() {
+                self.number(annotation.position());
+                self.number(annotation.length());
+            }
+        }
+    }
+}
+
+/// Set of the fingerprints of the entries logged so far, used to hide the duplicates.
+///
+/// Clones of a `Deduplicator` share the same set; see the [module documentation](self).
+#[derive(Clone, Debug, Default)]
+pub struct Deduplicator {
+    seen: Arc<Mutex<HashSet<Fingerprint>>>
+}
+impl Deduplicator {
+    /// Creates a new, empty set of fingerprints.
+    pub fn new() -> Deduplicator {
+        Default::default()
+    }
+
+    /// Returns `true` if an entry with the same fingerprint as `item` was already seen,
+    /// otherwise remembers its fingerprint and returns `false`.
+    ///
+    /// Tasks are never duplicates.
+    pub fn is_duplicate(&self, item: &LogItem) -> bool {
+        match Fingerprint::of_item(item) {
+            Some(fingerprint) => !self.seen.lock().unwrap().insert(fingerprint),
+            None => false
+        }
+    }
+
+    /// Forgets all the fingerprints seen so far.
+    pub fn clear(&self) {
+        self.seen.lock().unwrap().clear();
+    }
+}
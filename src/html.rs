@@ -0,0 +1,296 @@
+//! Rendering of log entries into a self-contained HTML page.
+//!
+//! The page is generated from the same layout used to print the entries to `stderr`,
+//! therefore the text inside the page is exactly the text that would be printed in the terminal;
+//! the only difference is that colors are replaced by CSS classes.
+//!
+//! # Example
+//! ```
+//! # fn main() -> prologue_logger::error::Result<()> {
+//! # use prologue_logger::{Entry, Task};
+//! # use prologue_logger::html::HtmlReport;
+//! let mut report = HtmlReport::new("Lint results");
+//!
+//! report.add_task("example", &Task::new("Checking", "example v0.1.0"));
+//! report.add_entry("example", &Entry::new_warning("variable does not need to be mutable")
+//!     .named_source("src/main.rs", 3, 9)
+//!     .new_line(3, "    let mut x = 42;")
+//!     .annotate_help(9, 4, "help: remove this `mut`")?
+//!     .annotate_warn(13, 1, "")?
+//!     .finish());
+//!
+//! // Obtain the page, e.g. to save it as a CI artifact.
+//! let page = report.to_string();
+//! assert!(page.contains(r#"<span class="severity warning">warning</span>"#));
+//! assert!(page.contains(r#"<span class="underline help">----</span><span class="underline warning">^</span>"#));
+//! assert!(page.contains(r#"id="file-src-main-rs-L3""#));
+//! assert!(page.contains(r#"<span class="count warning">1 warning</span>"#));
+//! # Ok(()) }
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+use crate::{Entry, EntryKind, MultiEntry, Task};
+use crate::layout::{Anchor, Layout, Style};
+use crate::theme::Theme;
+
+const STYLESHEET: &str = "\
+body { background-color: #1e1e1e; color: #d4d4d4; font-family: sans-serif; }
+details.target { margin: 0.5em 0; }
+details.target > summary { cursor: pointer; font-weight: bold; }
+pre.log { font-family: monospace; margin: 0.5em 0 0.5em 1.5em; }
+.emphasis, .gutter, .task, .severity { font-weight: bold; }
+";
+
+/// A log target inside an [`HtmlReport`].
+#[derive(Clone, Debug)]
+struct HtmlTarget {
+    name: String,
+    layout: Layout,
+    warnings: usize,
+    errors: usize
+}
+impl HtmlTarget {
+    fn count(&mut self, kind: EntryKind) {
+        match kind {
+            EntryKind::Error => self.errors += 1,
+            EntryKind::Warning => self.warnings += 1,
+            _ => {}
+        }
+    }
+}
+
+/// Self-contained HTML page containing the log entries of one or more targets.
+///
+/// Each target is displayed as a collapsible section whose summary contains the number of
+/// warnings and errors logged to it.
+/// Every file referenced by an entry and every source code line receive an anchor,
+/// so that it is possible to link them directly (e.g. `report.html#file-src-main-rs-L3`).
+/// When the names of two files lead to the same anchor, e.g. `src/main.rs` and `src-main.rs`,
+/// the anchors of the second file receive a numeric suffix (e.g. `file-src-main-rs-2-L3`).
+///
+/// The page is obtained through the [`Display`](std::fmt::Display) trait
+/// and it is colored with the colors of the [active theme](Theme::active), unless otherwise specified.
+///
+/// # Example
+/// ```
+/// # use prologue_logger::Entry;
+/// # use prologue_logger::html::HtmlReport;
+/// let mut report = HtmlReport::new("Lint results");
+/// report.add_entry("example", &Entry::new_warning("unused import").named_source("src/main.rs", 1, 5).finish());
+/// report.add_entry("example", &Entry::new_warning("unused import").named_source("src-main.rs", 1, 5).finish());
+///
+/// let page = report.to_string();
+/// assert!(page.contains(r#"id="file-src-main-rs""#));
+/// assert!(page.contains(r#"id="file-src-main-rs-2""#));
+/// ```
+#[derive(Clone, Debug)]
+pub struct HtmlReport {
+    title: String,
+    targets: Vec<HtmlTarget>,
+    theme: Option<Theme>
+}
+impl Display for HtmlReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut anchors = Anchors::default();
+        writeln!(f, "<!DOCTYPE html>")?;
+        writeln!(f, "<html lang=\"en\">")?;
+        writeln!(f, "<head>")?;
+        writeln!(f, "<meta charset=\"utf-8\">")?;
+        writeln!(f, "<title>{}</title>", Escaped(&self.title))?;
+        writeln!(f, "<style>\n{}{}</style>", STYLESHEET, Colors(self.theme.unwrap_or_else(Theme::active)))?;
+        writeln!(f, "</head>")?;
+        writeln!(f, "<body>")?;
+        writeln!(f, "<h1>{}</h1>", Escaped(&self.title))?;
+        for target in self.targets.iter() {
+            let id = unique_id(&mut anchors.ids, format!("target-{}", slug(&target.name)));
+            writeln!(f, "<details class=\"target\" id=\"{}\" open>", id)?;
+            write!(f, "<summary><span class=\"target-name\">{}</span> ", Escaped(&target.name))?;
+            write!(f, "<span class=\"count{}\">{} warning{}</span>, ",
+                   if target.warnings > 0 { " warning" } else { "" },
+                   target.warnings, if target.warnings == 1 { "" } else { "s" })?;
+            writeln!(f, "<span class=\"count{}\">{} error{}</span></summary>",
+                     if target.errors > 0 { " error" } else { "" },
+                     target.errors, if target.errors == 1 { "" } else { "s" })?;
+            write!(f, "<pre class=\"log\">")?;
+            write_layout(f, &target.layout, &mut anchors)?;
+            writeln!(f, "</pre>")?;
+            writeln!(f, "</details>")?;
+        }
+        writeln!(f, "</body>")?;
+        writeln!(f, "</html>")
+    }
+}
+impl HtmlReport {
+    /// Creates a new, empty report with the given `title`.
+    pub fn new<S: Into<String>>(title: S) -> HtmlReport {
+        HtmlReport { title: title.into(), targets: Vec::new(), theme: None }
+    }
+
+    /// Uses the given `theme` instead of the [active theme](Theme::active).
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = Some(theme);
+    }
+
+    /// Adds an [`Entry`] to the section of the given `target`,
+    /// increasing the warning/error count of the target accordingly.
+    ///
+    /// Targets are displayed in the order in which they receive their first entry.
+    pub fn add_entry<S: AsRef<str>>(&mut self, target: S, entry: &Entry) {
+        let target = self.target(target.as_ref());
+        target.count(entry.kind);
+        entry.layout(&mut target.layout, None);
+    }
+
+    /// Adds a [`MultiEntry`] to the section of the given `target`,
+    /// increasing the warning/error count of the target accordingly.
+    ///
+    /// Targets are displayed in the order in which they receive their first entry.
+    pub fn add_multi_entry<S: AsRef<str>>(&mut self, target: S, multi: &MultiEntry) {
+        let target = self.target(target.as_ref());
+        target.count(multi.kind());
+        multi.layout(&mut target.layout);
+    }
+
+    /// Adds a [`Task`] to the section of the given `target`.
+    ///
+    /// Targets are displayed in the order in which they receive their first entry.
+    pub fn add_task<S: AsRef<str>>(&mut self, target: S, task: &Task) {
+        let target = self.target(target.as_ref());
+        task.layout(&mut target.layout, None);
+    }
+
+    fn target(&mut self, name: &str) -> &mut HtmlTarget {
+        let index = match self.targets.iter().position(|t| t.name == name) {
+            Some(index) => index,
+            None => {
+                self.targets.push(HtmlTarget { name: name.to_owned(), layout: Layout::new(), warnings: 0, errors: 0 });
+                self.targets.len() - 1
+            }
+        };
+        &mut self.targets[index]
+    }
+}
+
+/// CSS rules assigning the colors of a theme to the classes of the spans.
+struct Colors(Theme);
+impl Display for Colors {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let theme = &self.0;
+        for style in [Style::Emphasis, Style::Gutter, Style::Task] {
+            if let (Some(class), Some(color)) = (style_class(style), theme.color(style)) {
+                writeln!(f, ".{} {{ color: {}; }}", class, color.hex())?;
+            }
+        }
+        for kind in [EntryKind::Help, EntryKind::Note, EntryKind::Warning, EntryKind::Error] {
+            writeln!(f, ".{} {{ color: {}; }}", kind.label(), theme.kind_color(kind).hex())?;
+        }
+        Ok(())
+    }
+}
+
+/// Text with the HTML (and XML) special characters escaped.
+pub(crate) struct Escaped<'a>(pub(crate) &'a str);
+impl Display for Escaped<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '&' => write!(f, "&amp;")?,
+                '<' => write!(f, "&lt;")?,
+                '>' => write!(f, "&gt;")?,
+                '"' => write!(f, "&quot;")?,
+                '\'' => write!(f, "&#39;")?,
+                c => write!(f, "{}", c)?
+            }
+        }
+        Ok(())
+    }
+}
+
+fn style_class(style: Style) -> Option<String> {
+    match style {
+        Style::Plain => None,
+        Style::Emphasis => Some("emphasis".to_owned()),
+        Style::Gutter => Some("gutter".to_owned()),
+        Style::Code => Some("code".to_owned()),
+        Style::Severity(kind) => Some(format!("severity {}", kind.label())),
+        Style::Underline(kind) => Some(format!("underline {}", kind.label())),
+        Style::Annotation(kind) => Some(format!("annotation {}", kind.label())),
+        Style::Task => Some("task".to_owned())
+    }
+}
+
+/// Converts a name into a string which can be safely used as (part of) an `id` attribute.
+fn slug(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_matches('-').to_owned()
+}
+
+/// Anchors of an [`HtmlReport`] page.
+#[derive(Default)]
+struct Anchors {
+    /// Every `id` attribute already written in the page.
+    ids: HashSet<String>,
+    /// Slug of every file, unique even if the names of two files lead to the same slug.
+    files: HashMap<Option<PathBuf>, String>,
+    file_slugs: HashSet<String>
+}
+impl Anchors {
+    fn file_slug(&mut self, file: &Option<PathBuf>) -> String {
+        if let Some(slug) = self.files.get(file) {
+            return slug.clone();
+        }
+        let slug = match file {
+            Some(file) => format!("file-{}", slug(&file.display().to_string())),
+            None => "file-anonymous".to_owned()
+        };
+        let slug = unique_id(&mut self.file_slugs, slug);
+        self.files.insert(file.clone(), slug.clone());
+        slug
+    }
+}
+
+/// Makes `id` unique by appending a counter if it has already been used in the page.
+fn unique_id(ids: &mut HashSet<String>, id: String) -> String {
+    let mut candidate = id.clone();
+    let mut counter = 1;
+    while ids.contains(&candidate) {
+        counter += 1;
+        candidate = format!("{}-{}", id, counter);
+    }
+    ids.insert(candidate.clone());
+    candidate
+}
+
+fn write_layout(f: &mut Formatter<'_>, layout: &Layout, anchors: &mut Anchors) -> std::fmt::Result {
+    for row in layout.rows() {
+        // Only the first occurrence of a file or line receives the anchor.
+        let id = match &row.anchor {
+            Some(Anchor::Location { file, .. }) => Some(anchors.file_slug(file)),
+            Some(Anchor::Line { file, line }) => Some(format!("{}-L{}", anchors.file_slug(file), line)),
+            None => None
+        };
+        if let Some(id) = id {
+            if anchors.ids.insert(id.clone()) {
+                write!(f, "<a id=\"{}\"></a>", id)?;
+            }
+        }
+        for span in row.spans.iter() {
+            match style_class(span.style) {
+                Some(class) => write!(f, "<span class=\"{}\">{}</span>", class, Escaped(&span.text))?,
+                None => write!(f, "{}", Escaped(&span.text))?
+            }
+        }
+        writeln!(f)?;
+    }
+    Ok(())
+}
@@ -1,5 +1,3 @@
-use std::fmt::{Debug, Display, Formatter};
-
 #[cfg(not(feature = "console"))]
 pub mod console {
     use std::fmt::{Debug, Display, Formatter};
@@ -33,7 +31,7 @@ pub mod console {
         #[allow(unused)]
         pub fn hidden(self) -> StyledObject<String> {
             let len = format!("{}", self.0).len();
-            StyledObject(format!("{: >len$}", len = len))
+            StyledObject(format!("{: >len$}", "", len = len))
         }
     }
 
@@ -41,50 +39,3 @@ pub mod console {
         StyledObject(object)
     }
 }
-
-#[derive(Copy, Clone, Debug)]
-pub struct LineStart;
-impl Display for LineStart {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", console::style("|").cyan().bright())
-    }
-}
-#[derive(Copy, Clone, Debug)]
-pub struct NoteDash;
-impl Display for NoteDash {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", console::style("=").cyan().bright())
-    }
-}
-#[derive(Copy, Clone, Debug)]
-pub struct Arrow;
-impl Display for Arrow {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", console::style("-->").cyan().bright())
-    }
-}
-#[derive(Copy, Clone, Debug)]
-pub struct Colon;
-impl Display for Colon {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", console::style(":").white().bright())
-    }
-}
-#[derive(Copy, Clone, Debug)]
-pub struct Title<T: Display>(pub T);
-impl<T: Display> Display for Title<T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", console::style(&self.0).white().bright())
-    }
-}
-#[derive(Copy, Clone, Debug)]
-pub struct AnnotationUnderline(pub(super) super::EntryKind, pub usize);
-impl Display for AnnotationUnderline {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        if self.0 == super::EntryKind::Help {
-            write!(f, "{:->len$}", self.0.style(""), len = self.1)
-        } else {
-            write!(f, "{:^>len$}", self.0.style(""), len = self.1)
-        }
-    }
-}
\ No newline at end of file
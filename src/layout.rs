@@ -0,0 +1,141 @@
+//! Renderer-independent layout of log entries.
+//!
+//! Every log entry is first laid out as a list of rows, each row being a sequence of
+//! styled spans of text; the various renderers (terminal, HTML, ...) then only decide
+//! how a given [`Style`] has to be displayed.
+//! This guarantees that all the renderers place every character at the same position.
+
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+use crate::EntryKind;
+use crate::theme::Theme;
+
+/// Semantic style of a span of text.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub(crate) enum Style {
+    /// Text with no particular meaning.
+    Plain,
+    /// Emphasized text, e.g. the message of an entry with a source.
+    Emphasis,
+    /// Line numbers, bars, arrows and other decorations on the left of the source code.
+    Gutter,
+    /// Source code.
+    Code,
+    /// Label of an entry, e.g. `warning`.
+    Severity(EntryKind),
+    /// Underline of an annotation, e.g. `^^^^`.
+    Underline(EntryKind),
+    /// Text of an annotation, together with the `|` connecting it to its underline.
+    Annotation(EntryKind),
+    /// "Verb" of a task, e.g. `Compiling`.
+    Task
+}
+/// Location referenced by a row.
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+pub(crate) enum Anchor {
+    /// The row contains the `--> file:line:position` reference of a source.
+    Location {
+        file: Option<PathBuf>,
+        line: usize,
+        position: usize
+    },
+    /// The row contains a line of source code.
+    Line {
+        file: Option<PathBuf>,
+        line: usize
+    }
+}
+
+/// A piece of text with an uniform style.
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+pub(crate) struct Span {
+    pub(crate) style: Style,
+    pub(crate) text: String
+}
+
+/// A single output line.
+#[derive(Clone, Eq, PartialEq, Debug, Hash, Default)]
+pub(crate) struct Row {
+    pub(crate) spans: Vec<Span>,
+    pub(crate) anchor: Option<Anchor>
+}
+
+/// List of rows produced by laying out one or more log entries.
+#[derive(Clone, Eq, PartialEq, Debug, Hash, Default)]
+pub(crate) struct Layout {
+    rows: Vec<Row>,
+    current: Row
+}
+impl Display for Layout {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let theme = Theme::active();
+        for row in self.rows() {
+            for span in row.spans.iter() {
+                write!(f, "{}", theme.paint(span.style, &span.text))?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+impl Layout {
+    pub(crate) fn new() -> Layout {
+        Default::default()
+    }
+
+    /// Appends `text` to the current row; every `\n` inside `text` starts a new row.
+    pub(crate) fn push<S: AsRef<str>>(&mut self, style: Style, text: S) {
+        let mut parts = text.as_ref().split('\n');
+        if let Some(part) = parts.next() {
+            self.push_span(style, part);
+        }
+        for part in parts {
+            self.newline();
+            self.push_span(style, part);
+        }
+    }
+
+    /// Appends `count` spaces to the current row.
+    pub(crate) fn pad(&mut self, count: usize) {
+        self.push_span(Style::Plain, &format!("{: >len$}", "", len = count));
+    }
+
+    /// Attaches an anchor to the current row.
+    pub(crate) fn anchor(&mut self, anchor: Anchor) {
+        self.current.anchor = Some(anchor);
+    }
+
+    /// Terminates the current row.
+    pub(crate) fn newline(&mut self) {
+        let row = std::mem::take(&mut self.current);
+        self.rows.push(row);
+    }
+
+    /// Returns the terminated rows.
+    pub(crate) fn rows(&self) -> &[Row] {
+        &self.rows
+    }
+
+    /// Returns the laid out text without any style.
+    pub(crate) fn plain(&self) -> String {
+        let mut text = String::new();
+        for row in self.rows() {
+            for span in row.spans.iter() {
+                text.push_str(&span.text);
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    fn push_span(&mut self, style: Style, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        match self.current.spans.last_mut() {
+            Some(span) if span.style == style => span.text.push_str(text),
+            _ => self.current.spans.push(Span { style, text: text.to_owned() })
+        }
+    }
+}
@@ -3,11 +3,36 @@
 //! It offers many features, like:
 //! * easy-to-use building patterns to customize the log entries;
 //! * annotating source code lines with the Rust-like `^^^` underline;
-//! * counting warnings and errors for multiple targets;
+//! * counting warnings and errors for multiple targets, optionally hiding the duplicate entries
+//!   (see the [`fingerprint`] module) and aborting after too many errors;
+//! * lints declared with [`declare_lint!`] and listed like `rustc -W help`, with `allow`, `warn`,
+//!   `deny` and `forbid` levels set from `rustc`-style flags like `-A unused -D warnings`
+//!   (see the [`lint`] module), and per-target `--cap-lints` and deny-warnings policies;
+//! * suppressing the known entries listed in a baseline file, reporting only the new ones
+//!   (see the [`baseline`] module);
+//! * nesting targets, e.g. workspace, package and file, with the counts of the children
+//!   included in the ones of their parents;
+//! * comparing the entries of two runs to report the new and resolved ones
+//!   (requires the `json` feature, see the `diff` module);
+//! * holding the entries of a target until it is finished, to display them as one block
+//!   or to sort them by location (see [`Buffering`]);
+//! * writing the entries of each target to one or more [sinks](sink), e.g. files,
+//!   optionally from a background thread;
+//! * capturing the logged entries in memory to test them, also against golden files
+//!   (see the [`capture`] and [`snapshot`] modules);
 //! * colored output to `stderr` (requires the `console` feature);
-//! * integration with the [`log`](https://docs.rs/log/latest/log/) API (requires the `log` feature);
+//! * integration with the [`log`](https://docs.rs/log/latest/log/) API, with global and per-target
+//!   level filtering (requires the `log` feature, see the `filter` module);
+//! * configuration of the logger through a builder or through environment variables
+//!   (requires the `log` feature, see the `builder` module);
+//! * integration with the [`tracing`](https://docs.rs/tracing/latest/tracing/) ecosystem through a subscriber layer
+//!   (requires the `tracing` feature, see the `subscriber` module);
+//! * serialization of the log entries with [`serde`](https://docs.rs/serde/latest/serde/) (requires the `serde` feature);
+//! * JSON output of the log entries, one object per line (requires the `json` feature);
 //! * integration with the [`indicatif`](https://docs.rs/indicatif/latest/indicatif/) crate (requires the `indicatif` feature);
-//! * color support with the [`console`](https://docs.rs/console/latest/console/) crate (requires the `console` feature).
+//! * color support with the [`console`](https://docs.rs/console/latest/console/) crate (requires the `console` feature);
+//! * rendering of the log entries into a self-contained HTML page (see the [`html`] module)
+//!   or into an SVG image (see the [`svg`] module), with customizable color [themes](theme).
 //!
 //! # Usage
 //!
@@ -202,6 +227,33 @@
 //! ```
 //! Notice that the lines are now aligned and there is no gap between the entries.
 //!
+//! # Inspecting entries
+//!
+//! Once built, an [`Entry`] can be inspected through its getters, e.g. to filter,
+//! sort or convert the entries produced by some tool.
+//! The source lines, annotations and notes are exposed as [`SourceLine`], [`Annotation`]
+//! and [`Note`] respectively.
+//! ```
+//! # fn main() -> prologue_logger::error::Result<()> {
+//! # use prologue_logger::{Entry, EntryKind};
+//! let entry = Entry::new_warning("variable does not need to be mutable")
+//!     .code("unused_mut")
+//!     .named_source("src/main.rs", 3, 9)
+//!     .new_line(3, "    let mut x = 42;")
+//!     .annotate_help(9, 4, "help: remove this `mut`")?
+//!     .finish();
+//!
+//! assert_eq!(entry.kind(), EntryKind::Warning);
+//! assert_eq!(entry.error_code(), Some("unused_mut"));
+//! assert_eq!(entry.location(), Some((3, 9)));
+//!
+//! let annotation = &entry.lines()[0].annotations()[0];
+//! assert_eq!(annotation.style(), EntryKind::Help);
+//! assert_eq!((annotation.position(), annotation.length()), (9, 4));
+//! assert_eq!(annotation.label(), "help: remove this `mut`");
+//! # Ok(()) }
+//! ```
+//!
 //! # Features
 //!
 //! ## [`console`](https://docs.rs/console/0.15.0/console/)
@@ -265,58 +317,119 @@
 
 use std::borrow::Cow;
 use std::cmp::Ordering;
+use std::collections::HashMap;
+#[cfg(feature = "log")]
+use std::collections::HashSet;
 use std::fmt::{Debug, Display, Formatter};
-use std::path::PathBuf;
-use std::sync::{Arc, Mutex};
+use std::path::{Path, PathBuf};
+#[cfg(feature = "log")]
+use std::ops::Deref;
+use std::sync::{Arc, Mutex, RwLock};
+use std::sync::atomic::{self, AtomicBool, AtomicUsize};
+#[cfg(feature = "log")]
+use log::{Metadata, Record};
+#[cfg(feature = "log")]
+use builder::PrologueLoggerBuilder;
 #[cfg(feature = "log")]
-use log::{LevelFilter, Metadata, Record};
+use filter::Filter;
 
+pub mod baseline;
+#[cfg(feature = "log")]
+pub mod builder;
+pub mod capture;
+#[cfg(feature = "json")]
+pub mod diff;
 pub mod error;
+pub mod fingerprint;
+#[cfg(feature = "log")]
+pub mod filter;
+pub mod html;
 mod internals;
-
+mod layout;
+pub mod lint;
+pub mod sink;
+pub mod snapshot;
+#[cfg(feature = "tracing")]
+pub mod subscriber;
+pub mod svg;
+pub mod theme;
+
+use baseline::Baseline;
+use capture::Capture;
+use fingerprint::Deduplicator;
+use lint::{Level, LintLevels, LintRef};
+use sink::Sink;
+#[cfg(feature = "log")]
+use sink::BackgroundHandle;
 use error::{Result, ErrorKind};
-use internals::*;
+use layout::{Anchor, Layout, Style};
 
+/// Kind of a [`Note`].
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
-enum NoteKind {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "lowercase"))]
+pub enum NoteKind {
+    /// A note added with [`EntrySourceBuilder::help`].
     Help,
+    /// A note added with [`EntrySourceBuilder::note`].
     Note
 }
-impl Display for NoteKind {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+impl NoteKind {
+    fn label(&self) -> &'static str {
         match self {
-            NoteKind::Help => write!(f, "{}", console::style("help").white().bright()),
-            NoteKind::Note => write!(f, "{}", console::style("note").white().bright())
+            NoteKind::Help => "help",
+            NoteKind::Note => "note"
         }
     }
 }
 
+/// A note displayed at the end of the source code of an [`Entry`],
+/// e.g. `= note: #[warn(unused_mut)] on by default`.
 #[derive(Clone, Eq, PartialEq, Debug, Hash)]
-struct Note {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Note {
     kind: NoteKind,
     text: String,
 }
-impl Display for Note {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+impl Note {
+    /// Returns the kind of the note.
+    pub fn kind(&self) -> NoteKind {
+        self.kind
+    }
+
+    /// Returns the text of the note, without the `note: ` or `help: ` prefix.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    fn layout(&self, out: &mut Layout, width: usize) {
         let mut lines = self.text.lines();
         if let Some(line) = lines.next() {
-            writeln!(f, "{: >len$} {} {}: {}", "", NoteDash, self.kind, line, len = f.width().unwrap_or(0))?;
+            out.pad(width + 1);
+            out.push(Style::Gutter, "=");
+            out.pad(1);
+            out.push(Style::Emphasis, self.kind.label());
+            out.push(Style::Plain, ": ");
+            out.push(Style::Plain, line);
+            out.newline();
         }
         for line in lines {
-            writeln!(f, "{: >len$}         {}", "", line, len = f.width().unwrap_or(0))?;
+            out.pad(width + 9);
+            out.push(Style::Plain, line);
+            out.newline();
         }
-        Ok(())
     }
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct AnnotationReference {
     position: usize,
+    #[cfg_attr(feature = "serde", serde(rename = "length"))]
     len: usize
 }
 impl PartialOrd for AnnotationReference {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.position.partial_cmp(&other.position)
+        Some(self.cmp(other))
     }
 }
 impl Ord for AnnotationReference {
@@ -330,15 +443,20 @@ impl From<(usize, usize)> for AnnotationReference {
     }
 }
 
+/// An annotation of a [`SourceLine`], i.e. an underlined range of characters
+/// with an optional label.
 #[derive(Clone, Eq, PartialEq, Debug, Hash)]
-struct Annotation {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Annotation {
     style: EntryKind,
+    #[cfg_attr(feature = "serde", serde(flatten))]
     reference: AnnotationReference,
+    #[cfg_attr(feature = "serde", serde(rename = "label"))]
     text: String
 }
 impl PartialOrd for Annotation {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.reference.partial_cmp(&other.reference)
+        Some(self.cmp(other))
     }
 }
 impl Ord for Annotation {
@@ -346,89 +464,131 @@ impl Ord for Annotation {
         self.reference.cmp(&other.reference)
     }
 }
-impl Display for Annotation {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.style.style(&self.text))
-    }
-}
 impl Annotation {
-    fn advance(&self, offset: &mut usize, f: &mut Formatter) -> std::fmt::Result {
-        write!(f, "{: >len$}", "", len = self.reference.position - *offset)?;
+    /// Returns the style of the annotation.
+    ///
+    /// Annotations with the [`Help`](EntryKind::Help) style are underlined with `-`,
+    /// all the others with `^`.
+    pub fn style(&self) -> EntryKind {
+        self.style
+    }
+
+    /// Returns the position of the first underlined character.
+    pub fn position(&self) -> usize {
+        self.reference.position
+    }
+
+    /// Returns the number of underlined characters.
+    pub fn length(&self) -> usize {
+        self.reference.len
+    }
+
+    /// Returns the label displayed next to the underline (possibly empty).
+    pub fn label(&self) -> &str {
+        &self.text
+    }
+
+    fn advance(&self, offset: &mut usize, out: &mut Layout) {
+        out.pad(self.reference.position - *offset);
         *offset = self.reference.position;
-        Ok(())
     }
 
-    fn draw_underline(&self, offset: &mut usize, f: &mut Formatter) -> std::fmt::Result {
-        self.advance(offset, f)?;
-        write!(f, "{}", AnnotationUnderline(self.style, self.reference.len))?;
+    fn draw_underline(&self, offset: &mut usize, out: &mut Layout) {
+        self.advance(offset, out);
+        let underline = if self.style == EntryKind::Help { "-" } else { "^" };
+        out.push(Style::Underline(self.style), underline.repeat(self.reference.len));
         *offset += self.reference.len;
-        Ok(())
     }
 
-    fn draw_text_arrow(&self, offset: &mut usize, f: &mut Formatter) -> std::fmt::Result {
-        self.advance(offset, f)?;
-        if self.text.len() > 0 {
-            write!(f, "{: <len$}", self.style.style("|"), len = self.reference.len)?;
+    fn draw_text_arrow(&self, offset: &mut usize, out: &mut Layout) {
+        self.advance(offset, out);
+        if !self.text.is_empty() {
+            out.push(Style::Annotation(self.style), "|");
+            out.pad(self.reference.len.saturating_sub(1));
         } else {
-            write!(f, "{: <len$}", "", len = self.reference.len)?;
+            out.pad(self.reference.len);
         }
         *offset += self.reference.len;
-        Ok(())
     }
 }
 
+/// A line of source code inside an [`Entry`], together with its annotations.
 #[derive(Clone, Eq, PartialEq, Debug, Hash)]
-struct SourceLine {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SourceLine {
     line: usize,
     contents: String,
     annotations: Vec<Annotation>
 }
-impl Display for SourceLine {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        // Get the offset of the line.
-        let width = f.width().unwrap_or_else(|| format!("{}", self.line).len());
-        writeln!(f, "{: <len$} {} {}", console::style(self.line).cyan().bright(), LineStart, self.contents, len = width)?;
-        if self.annotations.len() > 0 {
-            write!(f, "{: >len$} {}", "", LineStart, len = width)?;
+impl SourceLine {
+    fn layout(&self, out: &mut Layout, width: usize, file: &Option<PathBuf>) {
+        out.anchor(Anchor::Line { file: file.clone(), line: self.line });
+        out.push(Style::Gutter, format!("{: <len$}", self.line, len = width));
+        out.pad(1);
+        out.push(Style::Gutter, "|");
+        out.pad(1);
+        out.push(Style::Code, &self.contents);
+        out.newline();
+        if !self.annotations.is_empty() {
+            out.pad(width + 1);
+            out.push(Style::Gutter, "|");
             // Draw annotation lines.
             let mut offset = 0;
             for ann in self.annotations.iter() {
-                ann.draw_underline(&mut offset, f)?;
+                ann.draw_underline(&mut offset, out);
             }
             // Draw annotation texts.
             let mut annotations: Vec<&Annotation> = self.annotations.iter().collect();
             // Draw first annotation.
             if let Some(ann) = annotations.pop() {
-                write!(f, " {}", ann)?;
+                out.pad(1);
+                out.push(Style::Annotation(ann.style), &ann.text);
             }
             // Draw other annotations.
             while let Some(ann) = annotations.pop() {
-                write!(f, "\n{: >len$} {}", "", LineStart, len = width)?;
+                out.newline();
+                out.pad(width + 1);
+                out.push(Style::Gutter, "|");
                 offset = 0;
                 for prev_ann in annotations.iter() {
-                    prev_ann.draw_text_arrow(&mut offset, f)?;
+                    prev_ann.draw_text_arrow(&mut offset, out);
                 }
-                ann.draw_text_arrow(&mut offset, f)?;
-                write!(f, "\n{: >len$} {}", "", LineStart, len = width)?;
+                ann.draw_text_arrow(&mut offset, out);
+                out.newline();
+                out.pad(width + 1);
+                out.push(Style::Gutter, "|");
                 offset = 0;
                 for prev_ann in annotations.iter() {
-                    prev_ann.draw_text_arrow(&mut offset, f)?;
+                    prev_ann.draw_text_arrow(&mut offset, out);
                 }
-                ann.advance(&mut offset, f)?;
-                write!(f, "{}", ann)?;
+                ann.advance(&mut offset, out);
+                out.push(Style::Annotation(ann.style), &ann.text);
             }
-            writeln!(f)?;
+            out.newline();
         }
-        Ok(())
     }
-}
-impl SourceLine {
-    pub fn new<S: Into<String>>(line: usize, contents: S) -> SourceLine {
+
+    /// Returns the line number.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns the contents of the line.
+    pub fn contents(&self) -> &str {
+        &self.contents
+    }
+
+    /// Returns the annotations of the line, sorted by position.
+    pub fn annotations(&self) -> &[Annotation] {
+        &self.annotations
+    }
+
+    pub(crate) fn new<S: Into<String>>(line: usize, contents: S) -> SourceLine {
         let contents = contents.into();
         let annotations = Vec::new();
         SourceLine { line, contents, annotations }
     }
-    pub fn annotate<R: Into<AnnotationReference>, S: Into<String>>(&mut self, style: EntryKind, reference: R, text: S) -> Result<()> {
+    pub(crate) fn annotate<R: Into<AnnotationReference>, S: Into<String>>(&mut self, style: EntryKind, reference: R, text: S) -> Result<()> {
         let reference = reference.into();
         let text = text.into();
         let annotation = Annotation { style, reference, text };
@@ -453,73 +613,107 @@ impl SourceLine {
 }
 
 #[derive(Clone, Eq, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct Source {
+    #[cfg_attr(feature = "serde", serde(rename = "file"))]
     filename: Option<PathBuf>,
+    #[cfg_attr(feature = "serde", serde(rename = "line"))]
     line_number: usize,
     position: usize,
+    #[cfg_attr(feature = "serde", serde(default))]
     lines: Vec<SourceLine>,
-    notes: Vec<Note>
+    #[cfg_attr(feature = "serde", serde(default))]
+    notes: Vec<Note>,
+    /// Whether only the `-->` location is displayed, e.g. for the origin of a log record.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "std::ops::Not::not"))]
+    location_only: bool
 }
-impl Display for Source {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        // Get the offset of the line.
-        let width = if let Some(width) = f.width() {
-            width
-        } else {
-            let width = self.lines.iter()
-                .map(|line| line.line)
-                .max()
-                .unwrap_or(self.line_number);
-            format!("{}", width).len()
-        };
-        // Write "--> filename:row:position".
-        if let Some(filename) = &self.filename {
-            writeln!(f, "{: >len$}{} {}:{}:{}", "", Arrow, filename.display(), self.line_number, self.position, len = width)?;
-        } else {
-            writeln!(f, "{: >len$}{} <anonymous>:{}:{}", "", Arrow, self.line_number, self.position, len = width)?;
-        }
+impl Source {
+    fn layout(&self, out: &mut Layout, width: Option<usize>) {
+        let width = self.width(width);
+        self.layout_location(out, width);
         // Write an empty line.
-        writeln!(f, "{: >len$} {}", "", LineStart, len = width)?;
+        Source::layout_empty_line(out, width);
         // Write all (annotated) source line.
         for line in self.lines.iter() {
-            write!(f, "{:width$}", line, width = width)?;
+            line.layout(out, width, &self.filename);
         }
         // Write annotation texts.
         if let Some(line) = self.lines.last() {
-            if line.annotations.len() == 0 {
-                writeln!(f, "{: >len$} {}", "", LineStart, len = width)?;
+            if line.annotations.is_empty() {
+                Source::layout_empty_line(out, width);
             }
         } else {
-            writeln!(f, "{: >len$} {}", "", LineStart, len = width)?;
+            Source::layout_empty_line(out, width);
         }
 
         // Write final notes.
-        if self.notes.len() > 0 {
-            writeln!(f, "{: >len$} {}", "", LineStart, len = width)?;
+        if !self.notes.is_empty() {
+            Source::layout_empty_line(out, width);
         }
         for note in self.notes.iter() {
-            write!(f, "{:width$}", note, width = width)?;
+            note.layout(out, width);
         }
-        Ok(())
     }
-}
-impl Source {
-    pub fn new(line_number: usize, position: usize) -> Source {
-        Source { filename: None, line_number, position, lines: Vec::new(), notes: Vec::new() }
+
+    /// Gets the offset of the line, unless it is given.
+    fn width(&self, width: Option<usize>) -> usize {
+        width.unwrap_or_else(|| {
+            let width = self.lines.iter()
+                .map(|line| line.line)
+                .max()
+                .unwrap_or(self.line_number);
+            format!("{}", width).len()
+        })
+    }
+
+    /// Writes "--> filename:row:position".
+    fn layout_location(&self, out: &mut Layout, width: usize) {
+        out.anchor(Anchor::Location { file: self.filename.clone(), line: self.line_number, position: self.position });
+        out.pad(width);
+        out.push(Style::Gutter, "-->");
+        out.pad(1);
+        if let Some(filename) = &self.filename {
+            out.push(Style::Plain, format!("{}:{}:{}", filename.display(), self.line_number, self.position));
+        } else {
+            out.push(Style::Plain, format!("<anonymous>:{}:{}", self.line_number, self.position));
+        }
+        out.newline();
+    }
+
+    fn layout_empty_line(out: &mut Layout, width: usize) {
+        out.pad(width + 1);
+        out.push(Style::Gutter, "|");
+        out.newline();
+    }
+
+    pub(crate) fn new(line_number: usize, position: usize) -> Source {
+        Source { filename: None, line_number, position, lines: Vec::new(), notes: Vec::new(), location_only: false }
+    }
+
+    /// Creates a source displaying only its `-->` location, e.g. the origin of a log record.
+    #[cfg(any(feature = "log", feature = "tracing"))]
+    pub(crate) fn location(line_number: usize, position: usize) -> Source {
+        Source { location_only: true, ..Source::new(line_number, position) }
     }
 
-    pub fn set_filename<P: Into<PathBuf>>(&mut self, filename: P) {
+    pub(crate) fn set_filename<P: Into<PathBuf>>(&mut self, filename: P) {
         self.filename = Some(filename.into());
     }
 
-    pub fn add_line(&mut self, line: SourceLine) {
+    pub(crate) fn add_line(&mut self, line: SourceLine) {
         self.lines.push(line);
     }
 }
 
 /// Kind of the log line.
+///
+/// Kinds are ordered by severity, from [`Help`](EntryKind::Help) to [`Error`](EntryKind::Error).
+/// The [`Display`](std::fmt::Display) implementation produces the uncolored label,
+/// e.g. `warning`.
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Hash)]
-enum EntryKind {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "lowercase"))]
+pub enum EntryKind {
     /// Denotes an help.
     ///
     /// Usually used in additional lines for warnings or errors.
@@ -535,21 +729,16 @@ enum EntryKind {
 }
 impl Display for EntryKind {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        match self {
-            EntryKind::Help => write!(f, "{}", console::style("help").cyan().bright()),
-            EntryKind::Note => write!(f, "{}", console::style("note").green().bright()),
-            EntryKind::Warning => write!(f, "{}", console::style("warning").yellow().bright()),
-            EntryKind::Error => write!(f, "{}", console::style("error").red().bright())
-        }
+        (&self.label() as &dyn Display).fmt(f)
     }
 }
 impl EntryKind {
-    fn style<D: Display>(&self, object: D) -> console::StyledObject<D> {
+    fn label(&self) -> &'static str {
         match self {
-            EntryKind::Help => console::style(object).cyan().bright(),
-            EntryKind::Note => console::style(object).green().bright(),
-            EntryKind::Warning => console::style(object).yellow().bright(),
-            EntryKind::Error => console::style(object).red().bright()
+            EntryKind::Help => "help",
+            EntryKind::Note => "note",
+            EntryKind::Warning => "warning",
+            EntryKind::Error => "error"
         }
     }
 }
@@ -931,31 +1120,91 @@ impl EntrySourceBuilder {
 /// Contains all the information that needs to be displayed in the log and implements the
 /// [`Display`](std::fmt::Display) trait to ease use in formatting macros line `format!`
 /// or `print!`.
-#[derive(Clone, Debug)]
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Entry {
     kind: EntryKind,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    code: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    lint: Option<LintRef>,
+    #[cfg_attr(feature = "serde", serde(default))]
     bright: bool,
+    #[cfg_attr(feature = "serde", serde(rename = "message"))]
     text: String,
-    source: Option<Source>
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    source: Option<Source>,
+    /// Notes of an entry without source; otherwise the notes belong to the source.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    notes: Vec<Note>
 }
 impl Display for Entry {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let styled = console::style(&self.text);
-        let styled = if self.bright { styled.white().bright() } else { styled };
-        writeln!(f, "{}{} {}", self.kind, Colon, styled)?;
-        if let Some(source) = &self.source {
-            (source as &dyn Display).fmt(f)?;
-        }
-        if f.width().is_none() && self.source.is_some() {
-            writeln!(f)?;
-        }
-        Ok(())
+        let mut layout = Layout::new();
+        self.layout(&mut layout, f.width());
+        (&layout as &dyn Display).fmt(f)
     }
 }
 impl Entry {
+    fn layout(&self, out: &mut Layout, width: Option<usize>) {
+        out.push(Style::Severity(self.kind), self.kind.label());
+        if let Some(code) = &self.code {
+            out.push(Style::Severity(self.kind), format!("[{}]", code));
+        }
+        out.push(Style::Emphasis, ":");
+        out.pad(1);
+        out.push(if self.bright { Style::Emphasis } else { Style::Plain }, &self.text);
+        out.newline();
+        if let Some(source) = self.source.as_ref().filter(|source| source.location_only) {
+            source.layout_location(out, source.width(width));
+        } else if let Some(source) = &self.source {
+            source.layout(out, width);
+        } else if !self.notes.is_empty() {
+            let width = width.unwrap_or(1);
+            Source::layout_empty_line(out, width);
+            for note in self.notes.iter() {
+                note.layout(out, width);
+            }
+        }
+        if width.is_none() && (self.source.is_some() || !self.notes.is_empty()) {
+            out.newline();
+        }
+    }
+
     fn new<S: Into<String>>(kind: EntryKind, text: S) -> Entry {
         let text = text.into();
-        Entry { kind, bright: false, text, source: None }
+        Entry { kind, code: None, lint: None, bright: false, text, source: None, notes: Vec::new() }
+    }
+
+    /// Adds a note at the end of the entry, even if it has no source.
+    fn add_note(&mut self, note: Note) {
+        match &mut self.source {
+            Some(source) => source.notes.push(note),
+            None => self.notes.push(note)
+        }
+    }
+
+    /// Wraps a plain log record into an entry with the matching severity.
+    ///
+    /// If `location` is `true` and the record contains the file and line where it originated,
+    /// they are displayed as the `-->` location of the entry.
+    #[cfg(feature = "log")]
+    fn from_record(record: &Record, location: bool) -> Entry {
+        let kind = match record.level() {
+            log::Level::Error => EntryKind::Error,
+            log::Level::Warn => EntryKind::Warning,
+            log::Level::Info => EntryKind::Note,
+            log::Level::Debug | log::Level::Trace => EntryKind::Help
+        };
+        let text = format!("{}", record.args());
+        let mut entry = Entry::new(kind, text.trim_end_matches(['\n', '\r']));
+        if let (true, Some(file), Some(line)) = (location, record.file(), record.line()) {
+            let mut source = Source::location(line as usize, 1);
+            source.set_filename(file);
+            entry.bright = true;
+            entry.source = Some(source);
+        }
+        entry
     }
 
     /// Creates a new error entry.
@@ -1066,6 +1315,68 @@ impl Entry {
         Entry::new(EntryKind::Help, text)
     }
 
+    /// Assigns a code to the entry, e.g. to allow the user to look up
+    /// further information about an error.
+    ///
+    /// # Example
+    /// ```
+    /// # use prologue_logger::Entry;
+    /// let entry = Entry::new_error("mismatched types")
+    ///     .code("E0308");
+    ///
+    /// assert_eq!(format!("{}", entry), "error[E0308]: mismatched types\n");
+    /// ```
+    /// If the feature `console` is enabled, the code is printed with the same color as the
+    /// `error` string.
+    pub fn code<S: Into<String>>(mut self, code: S) -> Entry {
+        self.code = Some(code.into());
+        self
+    }
+
+    /// Returns the kind of the entry.
+    pub fn kind(&self) -> EntryKind {
+        self.kind
+    }
+
+    /// Returns the code of the entry, if any, as set by [`code`](Entry::code).
+    pub fn error_code(&self) -> Option<&str> {
+        self.code.as_deref()
+    }
+
+    /// Returns the name of the lint reporting the entry, if any;
+    /// see [`LintRegistry::entry`](lint::LintRegistry::entry).
+    pub fn lint(&self) -> Option<&str> {
+        self.lint.as_ref().map(|lint| lint.name.as_str())
+    }
+
+    /// Returns the message of the entry.
+    pub fn message(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the name of the file the entry refers to.
+    ///
+    /// Returns `None` if the entry has no source or if the source is anonymous.
+    pub fn file(&self) -> Option<&Path> {
+        self.source.as_ref().and_then(|s| s.filename.as_deref())
+    }
+
+    /// Returns the primary location of the entry, i.e. the line and position
+    /// displayed after `-->`, or `None` if the entry has no source.
+    pub fn location(&self) -> Option<(usize, usize)> {
+        self.source.as_ref().map(|s| (s.line_number, s.position))
+    }
+
+    /// Returns the source code lines of the entry, in the order in which they are displayed.
+    pub fn lines(&self) -> &[SourceLine] {
+        self.source.as_ref().map(|s| s.lines.as_slice()).unwrap_or(&[])
+    }
+
+    /// Returns the notes displayed at the end of the entry.
+    pub fn notes(&self) -> &[Note] {
+        self.source.as_ref().map(|s| s.notes.as_slice()).unwrap_or(&self.notes)
+    }
+
     /// Creates an anonymous source code and allows to configure it.
     ///
     /// This function takes the `Entry` by value and outputs an [`EntrySourceBuilder`]
@@ -1176,12 +1487,7 @@ impl Entry {
     /// ```
     #[cfg(feature = "log")]
     pub fn log<S: AsRef<str>>(self, target: S) {
-        match self.kind {
-            EntryKind::Error => log::error!(target: target.as_ref(), "{}", self),
-            EntryKind::Warning => log::warn!(target: target.as_ref(), "{}", self),
-            EntryKind::Note => log::info!(target: target.as_ref(), "{}", self),
-            EntryKind::Help => log::debug!(target: target.as_ref(), "{}", self)
-        }
+        LogItem::from(self).log(target.as_ref())
     }
 
     /// Logs the current `Entry` to the given `target`, consuming it.
@@ -1207,6 +1513,8 @@ impl Entry {
 
     /// Logs the current `Entry` to the predefined `target` inside a [`PrologueLogger`], consuming it.
     ///
+    /// The target is looked up with [`TargetList::route`].
+    ///
     /// # Example
     /// ```
     /// # use prologue_logger::{PrologueLogger, Entry};
@@ -1223,7 +1531,7 @@ impl Entry {
     /// # Ok(()) }
     /// ```
     pub fn log_to_prologue_logger<S: AsRef<str>>(self, target: S, logger: &PrologueLogger) -> Result<()> {
-        let target = logger.target_list.find(target);
+        let target = logger.target_list.route(target);
         if let Some(target) = target {
             target.log_entry(self)?;
         }
@@ -1239,35 +1547,50 @@ impl Entry {
 /// Moreover, when emitting the `MultiEntry`, the children entries will be consecutive
 /// (i.e., not separated by empty lines) and aligned (i.e. all code lines start at the same
 /// position).
-#[derive(Clone, Debug)]
+#[derive(Clone, Eq, PartialEq, Debug, Default, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MultiEntry {
     entries: Vec<Entry>
 }
 impl Display for MultiEntry {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut layout = Layout::new();
+        self.layout(&mut layout);
+        (&layout as &dyn Display).fmt(f)
+    }
+}
+impl MultiEntry {
+    fn layout(&self, out: &mut Layout) {
         let width = self.entries.iter()
             .map(|e| e.source.as_ref().map(|s| s.line_number).unwrap_or(1))
             .max().unwrap_or(1);
         let width = format!("{}", width).len();
         for entry in self.entries.iter() {
-            write!(f, "{:width$}", entry, width = width)?;
+            entry.layout(out, Some(width));
         }
-        writeln!(f)
+        out.newline();
     }
-}
-impl Default for MultiEntry {
-    fn default() -> Self {
-        MultiEntry {
-            entries: Vec::new()
-        }
+
+    /// Returns the kind of the most severe entry, which is the kind of the whole `MultiEntry`.
+    ///
+    /// An empty `MultiEntry` has the [`Help`](EntryKind::Help) kind.
+    pub fn kind(&self) -> EntryKind {
+        self.entries.iter()
+            .map(|e| e.kind)
+            .max()
+            .unwrap_or(EntryKind::Help)
     }
-}
-impl MultiEntry {
+
     /// Creates a new, empty `MultiEntry`.
     pub fn new() -> MultiEntry {
         Default::default()
     }
 
+    /// Returns the entries, in the order in which they were added.
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
     /// Adds an entry to this `MultiEntry`.
     ///
     /// # Example
@@ -1351,16 +1674,7 @@ impl MultiEntry {
     /// ```
     #[cfg(feature = "log")]
     pub fn log<S: AsRef<str>>(self, target: S) {
-        let kind = self.entries.iter()
-            .map(|e| e.kind)
-            .max()
-            .unwrap_or(EntryKind::Help);
-        match kind {
-            EntryKind::Error => log::error!(target: target.as_ref(), "{}", self),
-            EntryKind::Warning => log::warn!(target: target.as_ref(), "{}", self),
-            EntryKind::Note => log::info!(target: target.as_ref(), "{}", self),
-            EntryKind::Help => log::debug!(target: target.as_ref(), "{}", self)
-        }
+        LogItem::from(self).log(target.as_ref())
     }
 
     /// Logs the current `MultiEntry` to the given `target`, consuming it.
@@ -1391,6 +1705,8 @@ impl MultiEntry {
     /// Logs the current `MultiEntry` to the predefined `target` inside a [`PrologueLogger`],
     /// consuming it.
     ///
+    /// The target is looked up with [`TargetList::route`].
+    ///
     /// # Example
     /// ```
     /// # use prologue_logger::{PrologueLogger, Entry, MultiEntry};
@@ -1411,7 +1727,7 @@ impl MultiEntry {
     /// # Ok(()) }
     /// ```
     pub fn log_to_prologue_logger<S: AsRef<str>>(self, target: S, logger: &PrologueLogger) -> Result<()> {
-        let target = logger.target_list.find(target);
+        let target = logger.target_list.route(target);
         if let Some(target) = target {
             target.log_multi_entry(self)?;
         }
@@ -1440,16 +1756,39 @@ impl MultiEntry {
 /// i.e. the "verb" will be printed in bright green and the trailing text will be printed
 /// in white.
 #[derive(Clone, Eq, PartialEq, Debug, Hash)]
-pub struct Task(String, String);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Task {
+    task: String,
+    description: String
+}
 impl Display for Task {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "{: >width$} {}", console::style(&self.0).green().bright(), self.1, width = f.width().unwrap_or(12))
+        let mut layout = Layout::new();
+        self.layout(&mut layout, f.width());
+        (&layout as &dyn Display).fmt(f)
     }
 }
 impl Task {
+    fn layout(&self, out: &mut Layout, width: Option<usize>) {
+        out.push(Style::Task, format!("{: >len$}", self.task, len = width.unwrap_or(12)));
+        out.pad(1);
+        out.push(Style::Plain, &self.description);
+        out.newline();
+    }
+
     /// Creates a new entry given a `task` and a `description`.
     pub fn new<S1: Into<String>, S2: Into<String>>(task: S1, description: S2) -> Task {
-        Task(task.into(), description.into())
+        Task { task: task.into(), description: description.into() }
+    }
+
+    /// Returns the task, i.e. the "verb" on the left.
+    pub fn task(&self) -> &str {
+        &self.task
+    }
+
+    /// Returns the description of the task.
+    pub fn description(&self) -> &str {
+        &self.description
     }
 
     /// Logs the current `Task` to the given `target`, consuming it.
@@ -1476,6 +1815,8 @@ impl Task {
     /// Logs the current `Task` to the predefined `target` inside a [`PrologueLogger`],
     /// consuming it.
     ///
+    /// The target is looked up with [`TargetList::route`].
+    ///
     /// # Example
     /// ```
     /// # use prologue_logger::{PrologueLogger, Task};
@@ -1492,7 +1833,7 @@ impl Task {
     /// # Ok(()) }
     /// ```
     pub fn log_to_prologue_logger<S: AsRef<str>>(self, target: S, logger: &PrologueLogger) -> Result<()> {
-        let target = logger.target_list.find(target);
+        let target = logger.target_list.route(target);
         if let Some(target) = target {
             target.log_inline_entry(self)?;
         }
@@ -1519,58 +1860,677 @@ impl Task {
     /// ```
     #[cfg(feature = "log")]
     pub fn log<S: AsRef<str>>(self, target: S) {
-        log::info!(target: target.as_ref(), "{}", self)
+        LogItem::from(self).log(target.as_ref())
     }
 }
 
-/// Log target containing information about the number of logged warnings/errors.
-#[derive(Clone, Debug)]
-pub struct Target {
-    name: Arc<Cow<'static, str>>,
-    warnings: Arc<Mutex<usize>>,
-    errors: Arc<Mutex<usize>>,
-    #[cfg(feature = "indicatif")]
-    multi_progress: indicatif::MultiProgress
+/// Any of the entries which can be logged to a [`Target`].
+///
+/// When the `log` feature is enabled, the items logged through the `log` methods of [`Entry`],
+/// [`MultiEntry`] and [`Task`] are attached to the log record as key-value pairs
+/// (see the implementation of `log::kv::Source`), so that any logger can access their contents
+/// without parsing the (uncolored) text of the record:
+/// * `prologue`: the type of item, i.e. `entry`, `multi_entry` or `task`;
+///   on the records produced by the `log` methods, this value also carries the item itself,
+///   which any logger can retrieve with [`LogItem::from_record`];
+/// * `kind`: the kind of the entry, e.g. `warning`;
+/// * `code`: the code of the entry, if any;
+/// * `message`: the message of the entry, or the description of the task;
+/// * `file`, `line` and `column`: the primary location of the entry, if any;
+/// * `task`: the "verb" of the task;
+/// * `item`: the whole item, which can be serialized with `serde` (requires the `serde` feature).
+///
+/// For a [`MultiEntry`], the kind is the kind of the whole `MultiEntry` and the other
+/// values are taken from its first entry.
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(tag = "type", rename_all = "snake_case"))]
+pub enum LogItem {
+    /// A single [`Entry`].
+    Entry(Entry),
+    /// A [`MultiEntry`].
+    MultiEntry(MultiEntry),
+    /// A [`Task`].
+    Task(Task)
 }
-impl Target {
-    /// Creates a new target with the given `name`.
-    pub fn new<S: Into<Cow<'static, str>>>(name: S) -> Target {
-        let name = Arc::new(name.into());
-        let warnings = Arc::new(Mutex::new(0));
-        let errors = Arc::new(Mutex::new(0));
-        #[cfg(feature = "indicatif")]
-        let multi_progress = indicatif::MultiProgress::new();
-        Target { name, warnings, errors, #[cfg(feature = "indicatif")] multi_progress }
+impl Display for LogItem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LogItem::Entry(entry) => (entry as &dyn Display).fmt(f),
+            LogItem::MultiEntry(multi) => (multi as &dyn Display).fmt(f),
+            LogItem::Task(task) => (task as &dyn Display).fmt(f)
+        }
+    }
+}
+/// Key-value pairs describing the item, attached to the log records produced by
+/// the `log` methods of [`Entry`], [`MultiEntry`] and [`Task`].
+///
+/// # Example
+/// ```
+/// # use log::kv::{Key, Source};
+/// # use prologue_logger::{Entry, LogItem};
+/// let item = LogItem::from(Entry::new_warning("unused variable: `x`")
+///     .code("unused_variables"));
+///
+/// let kind = item.get(Key::from_str("kind")).unwrap();
+/// assert_eq!(kind.to_borrowed_str(), Some("warning"));
+/// let code = item.get(Key::from_str("code")).unwrap();
+/// assert_eq!(code.to_borrowed_str(), Some("unused_variables"));
+/// ```
+#[cfg(feature = "log")]
+impl log::kv::Source for LogItem {
+    fn visit<'kvs>(&'kvs self, visitor: &mut dyn log::kv::VisitSource<'kvs>) -> std::result::Result<(), log::kv::Error> {
+        self.visit_pairs(log::kv::Value::from(self.type_name()), visitor)
+    }
+}
+/// An item attached to the log record which carries it.
+///
+/// `log` only allows to downcast the values attached as errors, therefore the item is
+/// attached as an error, displayed as its type.
+#[cfg(feature = "log")]
+#[derive(Debug)]
+struct AttachedItem(LogItem);
+#[cfg(feature = "log")]
+impl Display for AttachedItem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.type_name())
+    }
+}
+#[cfg(feature = "log")]
+impl std::error::Error for AttachedItem {}
+#[cfg(feature = "log")]
+impl log::kv::Source for AttachedItem {
+    fn visit<'kvs>(&'kvs self, visitor: &mut dyn log::kv::VisitSource<'kvs>) -> std::result::Result<(), log::kv::Error> {
+        self.0.visit_pairs(log::kv::Value::from_dyn_error(self), visitor)
+    }
+}
+impl From<Entry> for LogItem {
+    fn from(entry: Entry) -> Self {
+        LogItem::Entry(entry)
+    }
+}
+impl From<MultiEntry> for LogItem {
+    fn from(multi: MultiEntry) -> Self {
+        LogItem::MultiEntry(multi)
+    }
+}
+impl From<Task> for LogItem {
+    fn from(task: Task) -> Self {
+        LogItem::Task(task)
     }
+}
+impl LogItem {
+    /// Visits the key-value pairs describing the item, with `marker` as the `prologue` value.
+    #[cfg(feature = "log")]
+    fn visit_pairs<'kvs>(&'kvs self, marker: log::kv::Value<'kvs>, visitor: &mut dyn log::kv::VisitSource<'kvs>) -> std::result::Result<(), log::kv::Error> {
+        use log::kv::{Key, Value};
 
-    /// Creates a new target with the given `name` and assigns an existing
-    /// `MultiProgress` to it.
-    #[cfg(feature = "indicatif")]
-    pub fn with_multi_progress<S: Into<Cow<'static, str>>>(name: S, multi_progress: indicatif::MultiProgress) -> Target {
-        let name = Arc::new(name.into());
-        let warnings = Arc::new(Mutex::new(0));
-        let errors = Arc::new(Mutex::new(0));
-        Target { name, warnings, errors, multi_progress }
+        let entry = match self {
+            LogItem::Entry(entry) => Some(entry),
+            LogItem::MultiEntry(multi) => multi.entries.first(),
+            LogItem::Task(_) => None
+        };
+        visitor.visit_pair(Key::from_str(LogItem::MARKER), marker)?;
+        if let Some(kind) = self.kind() {
+            visitor.visit_pair(Key::from_str("kind"), Value::from(kind.label()))?;
+        }
+        if let Some(entry) = entry {
+            if let Some(code) = &entry.code {
+                visitor.visit_pair(Key::from_str("code"), Value::from(code.as_str()))?;
+            }
+            visitor.visit_pair(Key::from_str("message"), Value::from(entry.text.as_str()))?;
+            if let Some(file) = entry.file().and_then(Path::to_str) {
+                visitor.visit_pair(Key::from_str("file"), Value::from(file))?;
+            }
+            if let Some((line, column)) = entry.location() {
+                visitor.visit_pair(Key::from_str("line"), Value::from(line))?;
+                visitor.visit_pair(Key::from_str("column"), Value::from(column))?;
+            }
+        }
+        if let LogItem::Task(task) = self {
+            visitor.visit_pair(Key::from_str("task"), Value::from(task.task.as_str()))?;
+            visitor.visit_pair(Key::from_str("message"), Value::from(task.description.as_str()))?;
+        }
+        #[cfg(feature = "serde")]
+        visitor.visit_pair(Key::from_str("item"), Value::from_serde(self))?;
+        Ok(())
     }
 
-    /// Obtains the name of this target.
+    /// Name of the type of item, as in the `prologue` key-value pair.
+    #[cfg(feature = "log")]
+    fn type_name(&self) -> &'static str {
+        match self {
+            LogItem::Entry(_) => "entry",
+            LogItem::MultiEntry(_) => "multi_entry",
+            LogItem::Task(_) => "task"
+        }
+    }
+
+    fn layout(&self, out: &mut Layout) {
+        match self {
+            LogItem::Entry(entry) => entry.layout(out, None),
+            LogItem::MultiEntry(multi) => multi.layout(out),
+            LogItem::Task(task) => task.layout(out, None)
+        }
+    }
+
+    /// Logs the item to the predefined `target` through the `log` API,
+    /// attaching its contents as key-value pairs.
+    #[cfg(feature = "log")]
+    fn log(self, target: &str) {
+        let level = match self.kind() {
+            Some(EntryKind::Error) => log::Level::Error,
+            Some(EntryKind::Warning) => log::Level::Warn,
+            Some(EntryKind::Note) | None => log::Level::Info,
+            Some(EntryKind::Help) => log::Level::Debug
+        };
+        if level > log::max_level() {
+            return;
+        }
+        let text = self.plain_text();
+        log::logger().log(&Record::builder()
+            .target(target)
+            .level(level)
+            .args(format_args!("{}", text))
+            .key_values(&AttachedItem(self))
+            .build());
+    }
+
+    /// Retrieves the item attached to a log record produced by the `log` methods
+    /// of [`Entry`], [`MultiEntry`] and [`Task`], or returns `None` if the record
+    /// was produced in any other way.
     ///
     /// # Example
     /// ```
-    /// # use prologue_logger::Target;
-    /// let target = Target::new("my-target");
-    /// assert_eq!(target.name(), "my-target");
+    /// # use std::sync::Mutex;
+    /// # use prologue_logger::{Entry, LogItem};
+    /// struct JsonLogger {
+    ///     lines: Mutex<Vec<String>>
+    /// }
+    /// impl log::Log for JsonLogger {
+    ///     fn enabled(&self, _: &log::Metadata) -> bool { true }
+    ///
+    ///     fn log(&self, record: &log::Record) {
+    ///         let line = match LogItem::from_record(record) {
+    ///             // Render the item in a custom way.
+    ///             Some(LogItem::Entry(entry)) => format!("{{\"message\":{:?}}}", entry.message()),
+    ///             Some(_) => return,
+    ///             // Not an item, just use the text.
+    ///             None => format!("{}", record.args())
+    ///         };
+    ///         self.lines.lock().unwrap().push(line);
+    ///     }
+    ///
+    ///     fn flush(&self) {}
+    /// }
+    ///
+    /// static LOGGER: JsonLogger = JsonLogger { lines: Mutex::new(Vec::new()) };
+    /// log::set_logger(&LOGGER).unwrap();
+    /// log::set_max_level(log::LevelFilter::Info);
+    ///
+    /// Entry::new_warning("unused variable: `x`").log("my-target");
+    /// log::warn!(target: "my-target", "disk full");
+    /// assert_eq!(*LOGGER.lines.lock().unwrap(), ["{\"message\":\"unused variable: `x`\"}", "disk full"]);
     /// ```
-    pub fn name(&self) -> &str {
-        self.name.as_ref()
+    #[cfg(feature = "log")]
+    pub fn from_record(record: &Record) -> Option<LogItem> {
+        let marker = record.key_values().get(log::kv::Key::from_str(LogItem::MARKER))?;
+        let attached = marker.to_borrowed_error()?.downcast_ref::<AttachedItem>()?;
+        Some(attached.0.clone())
     }
 
-    /// Obtains the number of warnings received by this target.
-    ///
-    /// # Example
-    /// ```
-    /// # use prologue_logger::{Target, Entry};
-    /// // Create the target.
+    /// Key of the log record value which denotes that the record contains a `LogItem`.
+    #[cfg(feature = "log")]
+    const MARKER: &'static str = "prologue";
+
+    /// Kind of the item, or `None` for a [`Task`].
+    pub(crate) fn kind(&self) -> Option<EntryKind> {
+        match self {
+            LogItem::Entry(entry) => Some(entry.kind),
+            LogItem::MultiEntry(multi) => Some(multi.kind()),
+            LogItem::Task(_) => None
+        }
+    }
+
+    /// Primary entry of the item, i.e. the entry itself or the first entry of a [`MultiEntry`].
+    fn primary_entry(&self) -> Option<&Entry> {
+        match self {
+            LogItem::Entry(entry) => Some(entry),
+            LogItem::MultiEntry(multi) => multi.entries.first(),
+            LogItem::Task(_) => None
+        }
+    }
+
+    /// Compares two items in the order of a [`Buffering::Sorted`] target.
+    ///
+    /// Items with a location come first, ordered by file, line, column and then severity;
+    /// the other items come last and compare equal, so that a stable sort keeps their order.
+    fn cmp_position(&self, other: &LogItem) -> Ordering {
+        fn located(item: &LogItem) -> Option<(&Entry, (usize, usize))> {
+            let entry = item.primary_entry()?;
+            Some((entry, entry.location()?))
+        }
+        match (located(self), located(other)) {
+            (Some((a, a_location)), Some((b, b_location))) => a.file().cmp(&b.file())
+                .then(a_location.cmp(&b_location))
+                .then(b.kind.cmp(&a.kind)),
+            (a, b) => a.is_none().cmp(&b.is_none())
+        }
+    }
+
+    /// Returns the text of the item without any color.
+    ///
+    /// # Example
+    /// ```
+    /// # use prologue_logger::{Entry, LogItem};
+    /// let item = LogItem::from(Entry::new_warning("unused variable: `x`"));
+    /// assert_eq!(item.plain_text(), "warning: unused variable: `x`\n");
+    /// ```
+    pub fn plain_text(&self) -> String {
+        let mut layout = Layout::new();
+        self.layout(&mut layout);
+        layout.plain()
+    }
+}
+
+/// Sinks of a [`Target`], shared by all its clones.
+///
+/// A [`TargetList`] also keeps a set of sinks, which are given to the targets it creates.
+#[derive(Clone, Default)]
+struct Sinks(Arc<RwLock<Vec<Arc<dyn Sink>>>>);
+impl Debug for Sinks {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Sinks")
+            .field("len", &self.0.read().unwrap().len())
+            .finish()
+    }
+}
+impl Sinks {
+    fn add<K: Sink + 'static>(&self, sink: K) {
+        self.add_shared(Arc::new(sink));
+    }
+
+    fn add_shared(&self, sink: Arc<dyn Sink>) {
+        self.0.write().unwrap().push(sink);
+    }
+
+    /// Returns a new set containing the same sinks, which can be extended independently.
+    fn detach(&self) -> Sinks {
+        Sinks(Arc::new(RwLock::new(self.0.read().unwrap().clone())))
+    }
+}
+impl Sink for Sinks {
+    fn write(&self, item: &LogItem) -> Result<()> {
+        self.0.read().unwrap().iter()
+            .map(|sink| sink.write(item))
+            .fold(Ok(()), Result::and)
+    }
+
+    fn write_block(&self, items: &[LogItem]) -> Result<()> {
+        self.0.read().unwrap().iter()
+            .map(|sink| sink.write_block(items))
+            .fold(Ok(()), Result::and)
+    }
+
+    fn write_from(&self, target: &str, item: &LogItem) -> Result<()> {
+        self.0.read().unwrap().iter()
+            .map(|sink| sink.write_from(target, item))
+            .fold(Ok(()), Result::and)
+    }
+
+    fn write_block_from(&self, target: &str, items: &[LogItem]) -> Result<()> {
+        self.0.read().unwrap().iter()
+            .map(|sink| sink.write_block_from(target, items))
+            .fold(Ok(()), Result::and)
+    }
+
+    fn flush(&self) -> Result<()> {
+        self.0.read().unwrap().iter()
+            .map(|sink| sink.flush())
+            .fold(Ok(()), Result::and)
+    }
+}
+
+/// Whether a [`Target`] holds its entries instead of writing them immediately.
+///
+/// Held entries are written as one contiguous block when the target is
+/// [finished](Target::finish), like `cargo` does with the diagnostics of the units
+/// compiled in parallel.
+///
+/// # Example
+/// ```
+/// # fn main() -> prologue_logger::error::Result<()> {
+/// # use prologue_logger::{Buffering, Entry, Target};
+/// # use prologue_logger::capture::Capture;
+/// let capture = Capture::new();
+/// let target = Target::with_capture("my-target", capture.clone());
+/// // Make the output independent of the order in which the entries are logged.
+/// target.set_buffering(Buffering::Sorted);
+///
+/// Entry::new_warning("unused import")
+///     .named_source("src/main.rs", 1, 5)
+///     .finish()
+///     .log_to_target(&target)?;
+/// Entry::new_note("no location")
+///     .log_to_target(&target)?;
+/// Entry::new_error("mismatched types")
+///     .named_source("src/lib.rs", 12, 9)
+///     .finish()
+///     .log_to_target(&target)?;
+/// Entry::new_warning("unused variable")
+///     .named_source("src/lib.rs", 12, 9)
+///     .finish()
+///     .log_to_target(&target)?;
+///
+/// target.finish()?;
+/// let entries = capture.entries();
+/// let messages: Vec<_> = entries.iter().map(Entry::message).collect();
+/// assert_eq!(messages, ["mismatched types", "unused variable", "unused import", "no location"]);
+/// # Ok(()) }
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, Default)]
+pub enum Buffering {
+    /// Every entry is written immediately.
+    #[default]
+    Streaming,
+    /// Every entry is held until the target is finished.
+    Buffered,
+    /// Errors are written immediately, while the other entries are held until the target is finished.
+    BufferedExceptErrors,
+    /// Every entry is held until the target is finished, and then the entries are sorted
+    /// by file, line, column and severity (most severe first), so that the output does not
+    /// depend on the order in which they were logged.
+    ///
+    /// A [`MultiEntry`] is sorted by its first entry. Entries without a location, including
+    /// the tasks, are written after the others, in the order in which they were logged.
+    Sorted
+}
+impl Buffering {
+    /// Returns `true` if `item` is held with this buffering mode.
+    fn holds(self, item: &LogItem) -> bool {
+        match self {
+            Buffering::Streaming => false,
+            Buffering::Buffered | Buffering::Sorted => true,
+            Buffering::BufferedExceptErrors => item.kind() != Some(EntryKind::Error)
+        }
+    }
+}
+
+/// Entries held by a buffered [`Target`].
+#[derive(Debug, Default)]
+struct Buffer {
+    buffering: Buffering,
+    items: Vec<LogItem>
+}
+
+/// Callback run when a [`Target`] reaches its error limit.
+type AbortCallback = Arc<dyn Fn(&Target) + Send + Sync>;
+
+/// Maximum number of errors of a [`Target`], and the callback to run when it is reached.
+#[derive(Clone, Default)]
+struct ErrorLimit {
+    limit: Option<usize>,
+    on_abort: Option<AbortCallback>
+}
+impl Debug for ErrorLimit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ErrorLimit")
+            .field("limit", &self.limit)
+            .field("on_abort", &self.on_abort.is_some())
+            .finish()
+    }
+}
+
+/// Maximum number of errors of all the targets of a [`TargetList`] together,
+/// with the warnings and errors they counted since the limit was set.
+#[derive(Debug)]
+struct RunLimit {
+    limit: usize,
+    warnings: AtomicUsize,
+    errors: AtomicUsize,
+    aborted: AtomicBool
+}
+impl RunLimit {
+    fn new(limit: usize) -> RunLimit {
+        RunLimit { limit, warnings: AtomicUsize::new(0), errors: AtomicUsize::new(0), aborted: AtomicBool::new(false) }
+    }
+}
+
+/// Increases `counter` unless it already reached `limit`, and returns its new value.
+fn increment_below(counter: &AtomicUsize, limit: Option<usize>) -> Option<usize> {
+    counter.fetch_update(atomic::Ordering::Relaxed, atomic::Ordering::Relaxed, |count| match limit {
+        Some(limit) if count >= limit => None,
+        _ => Some(count + 1)
+    }).ok().map(|count| count + 1)
+}
+
+/// Log target containing information about the number of logged warnings/errors.
+///
+/// Every entry logged to the target is written to its [sinks](sink), either immediately
+/// or, if the target is [buffered](Target::set_buffering), when the target is finished.
+/// Flushing or finishing a target, like flushing a [`TargetList`], goes on even if some
+/// of its sinks fail, in which case the first error is returned.
+///
+/// The clones of a target share its counters, sinks and settings, therefore a setting
+/// changed through one clone applies to all of them.
+#[derive(Clone, Debug)]
+pub struct Target {
+    name: Arc<Cow<'static, str>>,
+    warnings: Arc<AtomicUsize>,
+    errors: Arc<AtomicUsize>,
+    sinks: Sinks,
+    buffer: Arc<Mutex<Buffer>>,
+    deduplicator: Arc<RwLock<Option<Deduplicator>>>,
+    hidden: Arc<AtomicUsize>,
+    error_limit: Arc<Mutex<ErrorLimit>>,
+    aborted: Arc<AtomicBool>,
+    run_limit: Arc<RwLock<Option<Arc<RunLimit>>>>,
+    lint_levels: Arc<RwLock<LintLevels>>,
+    cap_lints: Arc<RwLock<Option<Level>>>,
+    deny_warnings: Arc<AtomicBool>,
+    baseline: Arc<RwLock<Option<Baseline>>>,
+    baselined: Arc<AtomicUsize>,
+    children: Arc<RwLock<Vec<Target>>>,
+    #[cfg(feature = "log")]
+    record_locations: Arc<AtomicBool>
+}
+impl Target {
+    /// Creates a new target with the given `name`.
+    ///
+    /// The target writes to `stderr` or, if the feature `indicatif` is enabled,
+    /// to a new `MultiProgress`.
+    pub fn new<S: Into<Cow<'static, str>>>(name: S) -> Target {
+        #[cfg(not(feature = "indicatif"))]
+        let sink = sink::StderrSink;
+        #[cfg(feature = "indicatif")]
+        let sink = sink::MultiProgressSink::new(indicatif::MultiProgress::new());
+        Target::with_sink(name, sink)
+    }
+
+    /// Creates a new target with the given `name`, whose only [`Sink`] is `sink`.
+    pub fn with_sink<S: Into<Cow<'static, str>>, K: Sink + 'static>(name: S, sink: K) -> Target {
+        let sinks = Sinks::default();
+        sinks.add(sink);
+        Target::with_sinks(name, sinks)
+    }
+
+    fn with_sinks<S: Into<Cow<'static, str>>>(name: S, sinks: Sinks) -> Target {
+        let name = Arc::new(name.into());
+        let warnings = Arc::new(AtomicUsize::new(0));
+        let errors = Arc::new(AtomicUsize::new(0));
+        #[cfg(feature = "log")]
+        let record_locations = Arc::new(AtomicBool::new(false));
+        let buffer = Arc::new(Mutex::new(Buffer::default()));
+        let deduplicator = Arc::new(RwLock::new(None));
+        let hidden = Arc::new(AtomicUsize::new(0));
+        let error_limit = Arc::new(Mutex::new(ErrorLimit::default()));
+        let aborted = Arc::new(AtomicBool::new(false));
+        let run_limit = Arc::new(RwLock::new(None));
+        let lint_levels = Arc::new(RwLock::new(LintLevels::new()));
+        let cap_lints = Arc::new(RwLock::new(None));
+        let deny_warnings = Arc::new(AtomicBool::new(false));
+        let baseline = Arc::new(RwLock::new(None));
+        let baselined = Arc::new(AtomicUsize::new(0));
+        let children = Arc::new(RwLock::new(Vec::new()));
+        Target {
+            name, warnings, errors, sinks, buffer, deduplicator, hidden, error_limit, aborted, run_limit,
+            lint_levels, cap_lints, deny_warnings, baseline, baselined, children,
+            #[cfg(feature = "log")] record_locations
+        }
+    }
+
+    /// Creates a new target with the given `name` and assigns an existing
+    /// `MultiProgress` to it.
+    #[cfg(feature = "indicatif")]
+    pub fn with_multi_progress<S: Into<Cow<'static, str>>>(name: S, multi_progress: indicatif::MultiProgress) -> Target {
+        Target::with_sink(name, sink::MultiProgressSink::new(multi_progress))
+    }
+
+    /// Creates a new target with the given `name` which records the logged entries
+    /// into `capture` instead of printing them.
+    ///
+    /// This is mainly useful in unit tests; see [`Capture`] for further information.
+    ///
+    /// # Example
+    /// ```
+    /// # use prologue_logger::{Entry, Target};
+    /// # use prologue_logger::capture::Capture;
+    /// let capture = Capture::new();
+    /// let target = Target::with_capture("my-target", capture.clone());
+    ///
+    /// // Nothing is printed.
+    /// Entry::new_warning("some warning")
+    ///     .log_to_target(&target);
+    ///
+    /// assert_eq!(target.warning_count(), 1);
+    /// assert_eq!(capture.text(), "warning: some warning\n");
+    /// ```
+    pub fn with_capture<S: Into<Cow<'static, str>>>(name: S, capture: Capture) -> Target {
+        Target::with_sink(name, capture)
+    }
+
+    /// Adds a [`Sink`] to the target, which from now on receives every entry logged to it.
+    pub fn add_sink<K: Sink + 'static>(&self, sink: K) {
+        self.sinks.add(sink);
+    }
+
+    /// Flushes all the sinks of the target.
+    ///
+    /// The entries held by a [buffered](Target::set_buffering) target are written first,
+    /// as if the target was [finished](Target::finish). The [children](Target::create_child)
+    /// of the target are flushed afterwards, in the order they were created.
+    ///
+    /// # Example
+    /// ```
+    /// # fn main() -> prologue_logger::error::Result<()> {
+    /// # use prologue_logger::{Buffering, Entry, Target};
+    /// # use prologue_logger::capture::Capture;
+    /// let capture = Capture::new();
+    /// let workspace = Target::with_capture("workspace", capture.clone());
+    /// let core = workspace.create_child("core");
+    /// core.set_buffering(Buffering::Buffered);
+    ///
+    /// Entry::new_warning("unused variable: `x`")
+    ///     .log_to_target(&core)?;
+    /// assert_eq!(capture.text(), "");
+    ///
+    /// workspace.flush()?;
+    /// assert_eq!(capture.text(), "warning: unused variable: `x`\n");
+    /// # Ok(()) }
+    /// ```
+    pub fn flush(&self) -> Result<()> {
+        let result = self.write_held().and(self.sinks.flush());
+        self.children().iter()
+            .map(Target::flush)
+            .fold(result, Result::and)
+    }
+
+    /// Sets whether the target holds the logged entries until it is [finished](Target::finish).
+    ///
+    /// Switching to [`Buffering::Streaming`] does not write the entries held so far.
+    ///
+    /// # Example
+    /// ```
+    /// # fn main() -> prologue_logger::error::Result<()> {
+    /// # use prologue_logger::{Buffering, Entry, Target, Task};
+    /// # use prologue_logger::capture::Capture;
+    /// let capture = Capture::new();
+    /// let target = Target::with_capture("my-target", capture.clone());
+    /// target.set_buffering(Buffering::BufferedExceptErrors);
+    ///
+    /// Task::new("Compiling", "my-target v0.1.0")
+    ///     .log_to_target(&target)?;
+    /// Entry::new_warning("unused variable: `x`")
+    ///     .log_to_target(&target)?;
+    /// Entry::new_error("mismatched types")
+    ///     .log_to_target(&target)?;
+    /// // Only the error has been written so far.
+    /// assert_eq!(capture.text(), "error: mismatched types\n");
+    ///
+    /// target.finish()?;
+    /// assert_eq!(capture.text(), concat!(
+    ///     "error: mismatched types\n",
+    ///     "   Compiling my-target v0.1.0\n",
+    ///     "warning: unused variable: `x`\n"
+    /// ));
+    /// # Ok(()) }
+    /// ```
+    pub fn set_buffering(&self, buffering: Buffering) {
+        self.buffer.lock().unwrap().buffering = buffering;
+    }
+
+    /// Returns the buffering mode of the target.
+    pub fn buffering(&self) -> Buffering {
+        self.buffer.lock().unwrap().buffering
+    }
+
+    /// Marks the target as finished, writing the entries held so far as one contiguous block
+    /// (see [`Sink::write_block`]).
+    ///
+    /// The target keeps its buffering mode, therefore entries logged afterwards are held
+    /// until the target is finished again. This method does nothing if no entry is held.
+    /// The [children](Target::create_child) of the target are finished afterwards,
+    /// in the order they were created.
+    pub fn finish(&self) -> Result<()> {
+        let result = self.write_held();
+        self.children().iter()
+            .map(Target::finish)
+            .fold(result, Result::and)
+    }
+
+    /// Writes the entries held by this target, without its children, as one block.
+    fn write_held(&self) -> Result<()> {
+        let items = {
+            let mut buffer = self.buffer.lock().unwrap();
+            let mut items = std::mem::take(&mut buffer.items);
+            if buffer.buffering == Buffering::Sorted {
+                items.sort_by(LogItem::cmp_position);
+            }
+            items
+        };
+        if items.is_empty() {
+            return Ok(());
+        }
+        self.sinks.write_block_from(&self.name, &items)
+    }
+
+    /// Obtains the name of this target.
+    ///
+    /// # Example
+    /// ```
+    /// # use prologue_logger::Target;
+    /// let target = Target::new("my-target");
+    /// assert_eq!(target.name(), "my-target");
+    /// ```
+    pub fn name(&self) -> &str {
+        self.name.as_ref()
+    }
+
+    /// Obtains the number of warnings received by this target and by its [children](Target::create_child).
+    ///
+    /// # Example
+    /// ```
+    /// # use prologue_logger::{Target, Entry};
+    /// // Create the target.
     /// let target = Target::new("my-target");
     /// // Log the first warning.
     /// Entry::new_warning("some warning")
@@ -1585,10 +2545,11 @@ impl Target {
     /// assert_eq!(target.warning_count(), 2);
     /// ```
     pub fn warning_count(&self) -> usize {
-        *self.warnings.lock().unwrap()
+        let children = self.children.read().unwrap();
+        self.warnings.load(atomic::Ordering::Relaxed) + children.iter().map(Target::warning_count).sum::<usize>()
     }
 
-    /// Obtains the number of warnings received by this target.
+    /// Obtains the number of errors received by this target and by its [children](Target::create_child).
     ///
     /// # Example
     /// ```
@@ -1608,60 +2569,573 @@ impl Target {
     /// assert_eq!(target.error_count(), 2);
     /// ```
     pub fn error_count(&self) -> usize {
-        *self.errors.lock().unwrap()
+        let children = self.children.read().unwrap();
+        self.errors.load(atomic::Ordering::Relaxed) + children.iter().map(Target::error_count).sum::<usize>()
+    }
+
+    /// Creates a child of this target with the given `name`, e.g. a package of a workspace
+    /// or a file of a package.
+    ///
+    /// The child writes to a copy of the sinks of this target and inherits a copy of its settings,
+    /// i.e. buffering mode, deduplicator, error limit and [abort callback](Target::on_abort),
+    /// lint levels, cap, deny-warnings and baseline; the color theme is shared by all the targets
+    /// anyway. The settings changed afterwards apply only to the target they are changed on,
+    /// e.g. to cap the lints of a vendored package without affecting its siblings.
+    /// Its warnings and errors are also counted by this target and by its ancestors,
+    /// while each target applies the error limit to its own errors only.
+    /// Finishing or flushing a target also finishes or flushes its children.
+    ///
+    /// To be found by name, the child must also be added to a [`TargetList`], e.g. with
+    /// [`TargetList::create_child_target`].
+    ///
+    /// # Example
+    /// ```
+    /// # fn main() -> prologue_logger::error::Result<()> {
+    /// # use prologue_logger::{Entry, Target};
+    /// # use prologue_logger::capture::Capture;
+    /// # use prologue_logger::lint::Level;
+    /// let workspace = Target::with_capture("workspace", Capture::new());
+    /// let core = workspace.create_child("core");
+    /// let cli = workspace.create_child("cli");
+    /// let main = cli.create_child("src/main.rs");
+    ///
+    /// Entry::new_warning("unused variable: `x`")
+    ///     .log_to_target(&core)?;
+    /// Entry::new_error("mismatched types")
+    ///     .log_to_target(&main)?;
+    ///
+    /// assert_eq!((workspace.warning_count(), workspace.error_count()), (1, 1));
+    /// assert_eq!((cli.warning_count(), cli.error_count()), (0, 1));
+    ///
+    /// let summaries = workspace.tree()
+    ///     .map(|(depth, target)| format!("{:width$}{}: {} warnings, {} errors", "", target.name(),
+    ///         target.warning_count(), target.error_count(), width = depth * 2))
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(summaries, [
+    ///     "workspace: 1 warnings, 1 errors",
+    ///     "  core: 1 warnings, 0 errors",
+    ///     "  cli: 0 warnings, 1 errors",
+    ///     "    src/main.rs: 0 warnings, 1 errors"
+    /// ]);
+    ///
+    /// // The settings changed afterwards do not affect the other targets of the tree.
+    /// cli.set_cap_lints(Some(Level::Allow));
+    /// Entry::new_error("mismatched types")
+    ///     .log_to_target(&cli)?;
+    /// Entry::new_error("mismatched types")
+    ///     .log_to_target(&workspace)?;
+    /// assert_eq!((cli.error_count(), workspace.error_count()), (1, 2));
+    /// # Ok(()) }
+    /// ```
+    pub fn create_child<S: Into<Cow<'static, str>>>(&self, name: S) -> Target {
+        let child = self.new_child(name);
+        self.children.write().unwrap().push(child.clone());
+        child
+    }
+
+    /// Creates a child of this target, inheriting its sinks and settings, without attaching it.
+    fn new_child<S: Into<Cow<'static, str>>>(&self, name: S) -> Target {
+        let child = Target::with_sinks(name, self.sinks.detach());
+        child.set_buffering(self.buffering());
+        child.set_deduplicator(self.deduplicator.read().unwrap().clone());
+        *child.error_limit.lock().unwrap() = self.error_limit.lock().unwrap().clone();
+        child.set_run_limit(self.run_limit.read().unwrap().clone());
+        child.set_lint_levels(self.lint_levels.read().unwrap().clone());
+        child.set_cap_lints(*self.cap_lints.read().unwrap());
+        child.set_deny_warnings(self.deny_warnings.load(atomic::Ordering::Relaxed));
+        child.set_baseline(self.baseline.read().unwrap().clone());
+        #[cfg(feature = "log")]
+        child.set_record_locations(self.record_locations.load(atomic::Ordering::Relaxed));
+        child
+    }
+
+    /// Returns the children of this target, in the order they were created.
+    pub fn children(&self) -> Vec<Target> {
+        self.children.read().unwrap().clone()
+    }
+
+    /// Returns this target and all its descendants, depth-first and in the order they were
+    /// created, each with its depth, i.e. `0` for this target, `1` for its children, and so on.
+    pub fn tree(&self) -> impl Iterator<Item = (usize, Target)> {
+        let mut tree = Vec::new();
+        let mut stack = vec![(0, self.clone())];
+        while let Some((depth, target)) = stack.pop() {
+            stack.extend(target.children().into_iter().rev().map(|child| (depth + 1, child)));
+            tree.push((depth, target));
+        }
+        tree.into_iter()
+    }
+
+    /// Sets the [`Deduplicator`] used to hide the duplicate entries, or disables the
+    /// deduplication if `None` (the default).
+    ///
+    /// Hidden entries are neither written nor counted as warnings or errors;
+    /// see the [`fingerprint`] module for further information.
+    pub fn set_deduplicator(&self, deduplicator: Option<Deduplicator>) {
+        *self.deduplicator.write().unwrap() = deduplicator;
+    }
+
+    /// Obtains the number of duplicate entries hidden by this target.
+    pub fn hidden_count(&self) -> usize {
+        self.hidden.load(atomic::Ordering::Relaxed)
+    }
+
+    /// Logs a note saying how many duplicate entries were hidden by this target, if any.
+    pub fn log_hidden_summary(&self) -> Result<()> {
+        match self.hidden_count() {
+            0 => Ok(()),
+            1 => self.emit(Entry::new_note("1 duplicate entry was hidden").into()),
+            hidden => self.emit(Entry::new_note(format!("{} duplicate entries were hidden", hidden)).into())
+        }
+    }
+
+    /// Sets the [`Baseline`] of the known entries to suppress, or disables it if `None`
+    /// (the default).
+    ///
+    /// Suppressed entries are neither written nor counted as warnings or errors;
+    /// see the [`baseline`] module for further information.
+    pub fn set_baseline(&self, baseline: Option<Baseline>) {
+        *self.baseline.write().unwrap() = baseline;
+    }
+
+    /// Obtains the number of known entries suppressed by the [baseline](Target::set_baseline)
+    /// of this target.
+    pub fn baselined_count(&self) -> usize {
+        self.baselined.load(atomic::Ordering::Relaxed)
+    }
+
+    fn log_entry(&self, entry: Entry) -> Result<()> {
+        self.log_counted(entry.into())
+    }
+
+    fn log_multi_entry(&self, multi: MultiEntry) -> Result<()> {
+        self.log_counted(multi.into())
+    }
+
+    /// Sets the maximum number of errors of the target, or removes the limit if `None`
+    /// (the default).
+    ///
+    /// When the limit is reached, the target writes `error: aborting due to N previous errors`
+    /// in the wording of `rustc`, runs the callback set with [`on_abort`](Target::on_abort),
+    /// if any, and discards every entry logged afterwards; see [`is_aborted`](Target::is_aborted).
+    ///
+    /// # Example
+    /// ```
+    /// # fn main() -> prologue_logger::error::Result<()> {
+    /// # use prologue_logger::{Entry, Target};
+    /// # use prologue_logger::capture::Capture;
+    /// let capture = Capture::new();
+    /// let target = Target::with_capture("my-target", capture.clone());
+    /// target.set_error_limit(Some(2));
+    ///
+    /// Entry::new_warning("unused variable: `x`")
+    ///     .log_to_target(&target)?;
+    /// for line in 1..=10_000 {
+    ///     if target.is_aborted() {
+    ///         break;
+    ///     }
+    ///     Entry::new_error(format!("invalid value on line {}", line))
+    ///         .log_to_target(&target)?;
+    /// }
+    ///
+    /// assert_eq!(target.error_count(), 2);
+    /// assert_eq!(capture.text(), concat!(
+    ///     "warning: unused variable: `x`\n",
+    ///     "error: invalid value on line 1\n",
+    ///     "error: invalid value on line 2\n",
+    ///     "error: aborting due to 2 previous errors; 1 warning emitted\n"
+    /// ));
+    /// # Ok(()) }
+    /// ```
+    pub fn set_error_limit(&self, limit: Option<usize>) {
+        self.error_limit.lock().unwrap().limit = limit;
+    }
+
+    /// Sets the `callback` to run once, when the target reaches its [error limit](Target::set_error_limit).
+    ///
+    /// The callback runs on the thread logging the last error, after the `aborting` entry is written.
+    pub fn on_abort<F: Fn(&Target) + Send + Sync + 'static>(&self, callback: F) {
+        self.error_limit.lock().unwrap().on_abort = Some(Arc::new(callback));
+    }
+
+    /// Returns `true` if the target reached its [error limit](Target::set_error_limit),
+    /// or the targets of its [`TargetList`] reached [theirs](TargetList::set_error_limit),
+    /// i.e. it discards every entry logged to it.
+    pub fn is_aborted(&self) -> bool {
+        self.aborted.load(atomic::Ordering::Relaxed)
+            || self.run_limit.read().unwrap().as_ref().is_some_and(|run| run.aborted.load(atomic::Ordering::Relaxed))
+    }
+
+    /// Sets the error limit shared with the other targets of a [`TargetList`].
+    fn set_run_limit(&self, run_limit: Option<Arc<RunLimit>>) {
+        *self.run_limit.write().unwrap() = run_limit;
+    }
+
+    /// Counts an error and emits it, unless the error limit of the target or of its
+    /// [`TargetList`] was already reached.
+    ///
+    /// The limits are checked while counting the error, therefore threads logging errors
+    /// concurrently never emit more errors than the limits.
+    fn log_error(&self, item: LogItem) -> Result<()> {
+        let limit = self.error_limit.lock().unwrap().limit;
+        let run_limit = self.run_limit.read().unwrap().clone();
+        let run_errors = match &run_limit {
+            Some(run) => match increment_below(&run.errors, Some(run.limit)) {
+                Some(errors) => Some(errors),
+                None => return Ok(())
+            },
+            None => None
+        };
+        let errors = match increment_below(&self.errors, limit) {
+            Some(errors) => errors,
+            None => {
+                if let Some(run) = &run_limit {
+                    run.errors.fetch_sub(1, atomic::Ordering::Relaxed);
+                }
+                return Ok(());
+            }
+        };
+        self.emit(item)?;
+        let warnings = self.warnings.load(atomic::Ordering::Relaxed);
+        self.check_error_limit(&self.aborted, limit, errors, warnings)?;
+        match (run_limit, run_errors) {
+            (Some(run), Some(errors)) => {
+                let warnings = run.warnings.load(atomic::Ordering::Relaxed);
+                self.check_error_limit(&run.aborted, Some(run.limit), errors, warnings)
+            },
+            _ => Ok(())
+        }
+    }
+
+    /// Writes the `aborting` entry and runs the [`on_abort`](Target::on_abort) callback if
+    /// `errors` reached `limit`, unless `aborted` was already set.
+    fn check_error_limit(&self, aborted: &AtomicBool, limit: Option<usize>, errors: usize, warnings: usize) -> Result<()> {
+        match limit {
+            Some(limit) if errors >= limit && !aborted.swap(true, atomic::Ordering::Relaxed) => {
+                let mut message = format!("aborting due to {}", if errors == 1 {
+                    "1 previous error".to_owned()
+                } else {
+                    format!("{} previous errors", errors)
+                });
+                match warnings {
+                    0 => {},
+                    1 => message.push_str("; 1 warning emitted"),
+                    warnings => message.push_str(&format!("; {} warnings emitted", warnings))
+                }
+                let result = self.emit(Entry::new_error(message).into());
+                let on_abort = self.error_limit.lock().unwrap().on_abort.clone();
+                if let Some(callback) = on_abort {
+                    callback(self);
+                }
+                result
+            },
+            _ => Ok(())
+        }
+    }
+
+    /// Sets the levels of the lints reported by the entries logged to this target;
+    /// see the [`lint`] module.
+    ///
+    /// Entries whose lint is allowed are discarded, the others become warnings or errors
+    /// according to the level of their lint, and are counted accordingly.
+    /// Every entry of a [`MultiEntry`] is leveled by its own lint: the `MultiEntry` is discarded
+    /// if its first entry is allowed, while the other allowed entries are removed from it.
+    /// The level of a lint is explained by a final note, even if the entry has no source.
+    ///
+    /// # Example
+    /// ```
+    /// # fn main() -> prologue_logger::error::Result<()> {
+    /// # use prologue_logger::{declare_lint, Target};
+    /// # use prologue_logger::capture::Capture;
+    /// # use prologue_logger::lint::{LintLevels, LintRegistry};
+    /// declare_lint!(UNUSED_KEY, Warn, "detects keys which are not used by any setting");
+    /// declare_lint!(DEPRECATED_KEY, Warn, "detects deprecated keys");
+    /// let registry = LintRegistry::new().register(&UNUSED_KEY).register(&DEPRECATED_KEY);
+    ///
+    /// let capture = Capture::new();
+    /// let target = Target::with_capture("my-target", capture.clone());
+    /// target.set_lint_levels(LintLevels::from_args(["-A", "unused-key"])?);
+    ///
+    /// registry.entry(&UNUSED_KEY, "unused key `edition`")?
+    ///     .log_to_target(&target)?;
+    /// registry.entry(&DEPRECATED_KEY, "the key `authors` is deprecated")?
+    ///     .named_source("Config.toml", 4, 1)
+    ///     .new_line(4, "authors = []")
+    ///     .annotate_warn(1, 7, "")?
+    ///     .finish()
+    ///     .log_to_target(&target)?;
+    /// registry.entry(&DEPRECATED_KEY, "the key `license-file` is deprecated")?
+    ///     .log_to_target(&target)?;
+    ///
+    /// assert_eq!(target.warning_count(), 2);
+    /// assert_eq!(capture.text(), concat!(
+    ///     "warning: the key `authors` is deprecated\n",
+    ///     " --> Config.toml:4:1\n",
+    ///     "  |\n",
+    ///     "4 | authors = []\n",
+    ///     "  | ^^^^^^^ \n",
+    ///     "  |\n",
+    ///     "  = note: `#[warn(deprecated_key)]` on by default\n",
+    ///     "\n",
+    ///     "warning: the key `license-file` is deprecated\n",
+    ///     "  |\n",
+    ///     "  = note: `#[warn(deprecated_key)]` on by default\n",
+    ///     "\n"
+    /// ));
+    /// # Ok(()) }
+    /// ```
+    pub fn set_lint_levels(&self, levels: LintLevels) {
+        *self.lint_levels.write().unwrap() = levels;
+    }
+
+    /// Caps the level of the warnings and errors of this target, as with the `--cap-lints`
+    /// flag of `rustc`, or removes the cap if `None` (the default).
+    ///
+    /// With a cap of [`Warn`](Level::Warn) errors become warnings, e.g. for the target
+    /// of a vendored dependency, while with a cap of [`Allow`](Level::Allow) warnings and errors
+    /// are discarded. Unlike `rustc`, the cap applies to all the entries, not only to the lints,
+    /// including every entry of a [`MultiEntry`].
+    /// The entries are written and counted with the capped severity.
+    ///
+    /// # Example
+    /// ```
+    /// # fn main() -> prologue_logger::error::Result<()> {
+    /// # use prologue_logger::{Entry, MultiEntry, Target};
+    /// # use prologue_logger::capture::Capture;
+    /// # use prologue_logger::lint::Level;
+    /// let capture = Capture::new();
+    /// let target = Target::with_capture("vendor/config", capture.clone());
+    /// target.set_cap_lints(Some(Level::Warn));
+    ///
+    /// Entry::new_error("missing key `name`")
+    ///     .log_to_target(&target)?;
+    /// MultiEntry::new()
+    ///     .entry(Entry::new_warning("unused key `edition`"))
+    ///     .entry(Entry::new_error("invalid value for `version`"))
+    ///     .log_to_target(&target)?;
+    ///
+    /// assert_eq!(target.error_count(), 0);
+    /// assert_eq!(target.warning_count(), 2);
+    /// assert_eq!(capture.text(), concat!(
+    ///     "warning: missing key `name`\n",
+    ///     "warning: unused key `edition`\n",
+    ///     "warning: invalid value for `version`\n",
+    ///     "\n"
+    /// ));
+    /// # Ok(()) }
+    /// ```
+    pub fn set_cap_lints(&self, cap: Option<Level>) {
+        *self.cap_lints.write().unwrap() = cap;
+    }
+
+    /// Sets whether the warnings of this target become errors, as with `-D warnings` but for
+    /// all the entries, not only the lints; the default is `false`.
+    ///
+    /// The entries, including every entry of a [`MultiEntry`], are written and counted as errors,
+    /// unless a [cap](Target::set_cap_lints) lowers them again.
+    ///
+    /// # Example
+    /// ```
+    /// # fn main() -> prologue_logger::error::Result<()> {
+    /// # use prologue_logger::{Entry, Target};
+    /// # use prologue_logger::capture::Capture;
+    /// let capture = Capture::new();
+    /// let target = Target::with_capture("strict", capture.clone());
+    /// target.set_deny_warnings(true);
+    ///
+    /// Entry::new_warning("unused key `edition`")
+    ///     .log_to_target(&target)?;
+    ///
+    /// assert_eq!(target.warning_count(), 0);
+    /// assert_eq!(target.error_count(), 1);
+    /// assert_eq!(capture.text(), "error: unused key `edition`\n");
+    /// # Ok(()) }
+    /// ```
+    pub fn set_deny_warnings(&self, enabled: bool) {
+        self.deny_warnings.store(enabled, atomic::Ordering::Relaxed);
+    }
+
+    /// Sets the kind of every entry of an item from the [lint levels](Target::set_lint_levels), the
+    /// [deny-warnings](Target::set_deny_warnings) setting and the [cap](Target::set_cap_lints)
+    /// of this target, or returns `None` if the item is allowed.
+    ///
+    /// A [`MultiEntry`] is allowed if its first entry is; otherwise its allowed entries are removed.
+    fn apply_severity(&self, mut item: LogItem) -> Option<LogItem> {
+        match &mut item {
+            LogItem::Entry(entry) => {
+                if !self.apply_level(entry) {
+                    return None;
+                }
+            },
+            LogItem::MultiEntry(multi) => {
+                let mut primary = true;
+                let mut allowed = multi.entries.is_empty();
+                multi.entries.retain_mut(|entry| {
+                    let kept = self.apply_level(entry);
+                    allowed |= primary && !kept;
+                    primary = false;
+                    kept
+                });
+                if allowed {
+                    return None;
+                }
+            },
+            LogItem::Task(_) => {}
+        }
+        Some(item)
+    }
+
+    /// Sets the kind of an entry, explaining the level of its lint with a note,
+    /// and returns `false` if the entry is allowed.
+    fn apply_level(&self, entry: &mut Entry) -> bool {
+        let (mut level, mut note) = match (&entry.lint, entry.kind) {
+            (Some(lint), _) => {
+                let resolved = self.lint_levels.read().unwrap().resolve(lint);
+                (resolved.level, Some(resolved.note))
+            },
+            (None, EntryKind::Error) => (Level::Deny, None),
+            (None, EntryKind::Warning) => (Level::Warn, None),
+            (None, _) => return true
+        };
+        if level == Level::Warn && self.deny_warnings.load(atomic::Ordering::Relaxed) {
+            level = Level::Deny;
+            note = entry.lint().map(|lint| format!("`-D {}` implied by `-D warnings`", lint.replace('_', "-")));
+        }
+        if let Some(cap) = *self.cap_lints.read().unwrap() {
+            if level > cap {
+                level = cap;
+                note = None;
+            }
+        }
+        match level.entry_kind() {
+            Some(kind) => entry.kind = kind,
+            None => return false
+        }
+        if let Some(note) = note {
+            entry.add_note(Note { kind: NoteKind::Note, text: note });
+        }
+        true
+    }
+
+    /// Counts and emits an entry, unless it is a duplicate to hide, it is allowed,
+    /// it is known by the baseline or the target has [aborted](Target::is_aborted).
+    fn log_counted(&self, item: LogItem) -> Result<()> {
+        if self.is_aborted() {
+            return Ok(());
+        }
+        let item = match self.apply_severity(item) {
+            Some(item) => item,
+            None => return Ok(())
+        };
+        let duplicate = self.deduplicator.read().unwrap().as_ref()
+            .map(|deduplicator| deduplicator.is_duplicate(&item))
+            .unwrap_or(false);
+        if duplicate {
+            self.hidden.fetch_add(1, atomic::Ordering::Relaxed);
+            return Ok(());
+        }
+        let known = self.baseline.read().unwrap().as_ref()
+            .map(|baseline| baseline.is_known(&item))
+            .unwrap_or(false);
+        if known {
+            self.baselined.fetch_add(1, atomic::Ordering::Relaxed);
+            return Ok(());
+        }
+        match item.kind() {
+            Some(EntryKind::Error) => self.log_error(item),
+            Some(kind) => {
+                self.count(kind);
+                self.emit(item)
+            },
+            None => self.emit(item)
+        }
     }
 
-    fn log_entry(&self, entry: Entry) -> Result<()> {
-        match entry.kind {
-            EntryKind::Error => { *self.errors.lock().unwrap() += 1; },
-            EntryKind::Warning => { *self.warnings.lock().unwrap() += 1; },
-            _ => {}
+    /// Increases the warning count if `kind` is a warning; errors are counted by `log_error`.
+    fn count(&self, kind: EntryKind) {
+        if kind == EntryKind::Warning {
+            self.warnings.fetch_add(1, atomic::Ordering::Relaxed);
+            if let Some(run) = self.run_limit.read().unwrap().as_ref() {
+                run.warnings.fetch_add(1, atomic::Ordering::Relaxed);
+            }
         }
-        #[cfg(not(feature = "indicatif"))]
-        eprint!("{}", entry);
-        #[cfg(feature = "indicatif")]
-        self.multi_progress.println(format!("{}", entry))?;
-        Ok(())
     }
 
-    fn log_multi_entry(&self, multi: MultiEntry) -> Result<()> {
-        let kind = multi.entries.iter()
-            .map(|e| e.kind)
-            .max()
-            .unwrap_or(EntryKind::Help);
-        match kind {
-            EntryKind::Error => { *self.errors.lock().unwrap() += 1; },
-            EntryKind::Warning => { *self.warnings.lock().unwrap() += 1; },
-            _ => {}
+    fn log_inline_entry(&self, entry: Task) -> Result<()> {
+        self.emit(entry.into())
+    }
+
+    #[cfg(feature = "log")]
+    fn log_item(&self, item: LogItem) -> Result<()> {
+        match item {
+            LogItem::Entry(entry) => self.log_entry(entry),
+            LogItem::MultiEntry(multi) => self.log_multi_entry(multi),
+            LogItem::Task(task) => self.log_inline_entry(task)
         }
-        #[cfg(not(feature = "indicatif"))]
-        eprint!("{}", multi);
-        #[cfg(feature = "indicatif")]
-        self.multi_progress.println(format!("{}", multi))?;
-        Ok(())
     }
 
-    fn log_inline_entry(&self, entry: Task) -> Result<()> {
-        #[cfg(not(feature = "indicatif"))]
-        eprint!("{}", entry);
-        #[cfg(feature = "indicatif")]
-            self.multi_progress.println(format!("{}", entry))?;
-        Ok(())
+    fn emit(&self, item: LogItem) -> Result<()> {
+        {
+            let mut buffer = self.buffer.lock().unwrap();
+            if buffer.buffering.holds(&item) {
+                buffer.items.push(item);
+                return Ok(());
+            }
+        }
+        self.sinks.write_from(&self.name, &item)
+    }
+
+    /// Sets whether the plain log records received by this target display the file and line
+    /// where they originated as a `-->` location.
+    ///
+    /// By default, the location is not displayed.
+    #[cfg(feature = "log")]
+    pub fn set_record_locations(&self, enabled: bool) {
+        self.record_locations.store(enabled, atomic::Ordering::Relaxed);
     }
 
     /// Logs a generic log record, increasing the warning/error count accordingly.
-    #[cfg(any(feature = "log"))]
+    ///
+    /// Records produced by the `log` methods of [`Entry`], [`MultiEntry`] and [`Task`]
+    /// are emitted as they are; any other record (e.g. one produced by a dependency through
+    /// the `log` macros) is wrapped into an [`Entry`] with the matching severity:
+    /// `Error` becomes an error, `Warn` a warning, `Info` a note and `Debug` or `Trace` a help.
+    ///
+    /// # Example
+    /// ```
+    /// # use prologue_logger::Target;
+    /// # use prologue_logger::capture::Capture;
+    /// let capture = Capture::new();
+    /// let target = Target::with_capture("example", capture.clone());
+    /// target.set_record_locations(true);
+    ///
+    /// target.log_record(&log::Record::builder()
+    ///     .target("example")
+    ///     .level(log::Level::Warn)
+    ///     .args(format_args!("disk full"))
+    ///     .file(Some("src/storage.rs"))
+    ///     .line(Some(42))
+    ///     .build())?;
+    /// // The severity comes from the level of the record, whatever its message.
+    /// target.log_record(&log::Record::builder()
+    ///     .target("example")
+    ///     .level(log::Level::Error)
+    ///     .args(format_args!("note: retrying"))
+    ///     .build())?;
+    ///
+    /// assert_eq!((target.warning_count(), target.error_count()), (1, 1));
+    /// assert_eq!(capture.text(), "warning: disk full\n  --> src/storage.rs:42:1\n\nerror: note: retrying\n");
+    /// # Ok::<(), prologue_logger::error::Error>(())
+    /// ```
+    #[cfg(feature = "log")]
     pub fn log_record(&self, record: &log::Record) -> Result<()> {
-        match record.level() {
-            log::Level::Error => { *self.errors.lock().unwrap() += 1; },
-            log::Level::Warn => { *self.warnings.lock().unwrap() += 1; },
-            _ => {}
+        match LogItem::from_record(record) {
+            Some(item) => self.log_item(item),
+            None => {
+                let location = self.record_locations.load(atomic::Ordering::Relaxed);
+                self.log_entry(Entry::from_record(record, location))
+            }
         }
-        #[cfg(not(feature = "indicatif"))]
-        eprint!("{}", record.args());
-        #[cfg(feature = "indicatif")]
-        self.multi_progress.println(format!("{}", record.args()))?;
-        Ok(())
     }
 
     /// Executes the given `callback` if the target received at least one warning.
@@ -1730,16 +3204,35 @@ impl Target {
 }
 
 /// A list of log targets.
+///
+/// Targets are indexed by name and the list is shared by all its clones, so that it can be
+/// looked up concurrently by many threads, e.g. by a [`PrologueLogger`] for every record.
+/// Operations on every target, e.g. [`flush`](TargetList::flush), visit the targets
+/// in the order in which they were added.
 #[derive(Clone, Debug)]
 pub struct TargetList {
-    list: Arc<Mutex<Vec<Target>>>,
+    list: Arc<RwLock<Targets>>,
+    routing: Arc<RwLock<Routing>>,
+    /// Sinks of the new targets, if configured by the builder; otherwise the new targets
+    /// write to `stderr`.
+    sinks: Option<Sinks>,
+    deduplicator: Arc<RwLock<Option<Deduplicator>>>,
+    error_limit: Arc<RwLock<Option<Arc<RunLimit>>>>,
+    lint_levels: Arc<RwLock<Option<LintLevels>>>,
+    baseline: Arc<RwLock<Option<Baseline>>>,
     #[cfg(feature = "indicatif")]
     multi_progress: indicatif::MultiProgress
 }
 impl Default for TargetList {
     fn default() -> Self {
         TargetList {
-            list: Arc::new(Mutex::new(Vec::new())),
+            list: Arc::new(RwLock::new(Targets::default())),
+            routing: Arc::new(RwLock::new(Routing::default())),
+            sinks: None,
+            deduplicator: Arc::new(RwLock::new(None)),
+            error_limit: Arc::new(RwLock::new(None)),
+            lint_levels: Arc::new(RwLock::new(None)),
+            baseline: Arc::new(RwLock::new(None)),
             #[cfg(feature = "indicatif")]
             multi_progress: indicatif::MultiProgress::new()
         }
@@ -1767,11 +3260,141 @@ impl TargetList {
     /// target.if_errors(errors_callback);
     /// ```
     pub fn find<S: AsRef<str>>(&self, name: S) -> Option<Target> {
-        let name = name.as_ref();
-        let target = self.list.lock().unwrap().iter()
-            .find(|t| t.name.as_ref() == name)
-            .map(|t| t.to_owned());
-        target
+        self.list.read().unwrap().get(name.as_ref()).cloned()
+    }
+
+    /// Finds the target which receives the entries logged with the given `name`
+    /// through a [`PrologueLogger`].
+    ///
+    /// Targets are looked up hierarchically, i.e. if there is no target called
+    /// `my_crate::parser::lexer`, then `my_crate::parser` is used, then `my_crate`;
+    /// this allows to route the records of the `log` macros, whose default target
+    /// is the module path.
+    /// If no target matches, the [default target](TargetList::set_default_target) is used, if any.
+    ///
+    /// # Example
+    /// ```
+    /// # use prologue_logger::TargetList;
+    /// # fn main() -> prologue_logger::error::Result<()> {
+    /// let target_list = TargetList::new();
+    /// target_list.create_target("my_crate")?;
+    /// target_list.create_target("other")?;
+    ///
+    /// assert_eq!(target_list.route("my_crate::parser").unwrap().name(), "my_crate");
+    /// assert!(target_list.route("my_crate_utils").is_none());
+    ///
+    /// // Send everything else to `other`.
+    /// target_list.set_default_target("other");
+    /// assert_eq!(target_list.route("my_crate_utils").unwrap().name(), "other");
+    /// # Ok(()) }
+    /// ```
+    pub fn route<S: AsRef<str>>(&self, name: S) -> Option<Target> {
+        let list = self.list.read().unwrap();
+        let mut name = name.as_ref();
+        loop {
+            if let Some(target) = list.get(name) {
+                return Some(target.clone());
+            }
+            match name.rfind("::") {
+                Some(index) => name = &name[..index],
+                None => break
+            }
+        }
+        let routing = self.routing.read().unwrap();
+        routing.default_target.as_ref().and_then(|name| list.get(name)).cloned()
+    }
+
+    /// Sets the target which receives the entries that cannot be routed to any other target.
+    ///
+    /// The target is looked up by name every time it is needed, therefore it does not need
+    /// to exist when this method is called. See [`route`](TargetList::route) for further information.
+    pub fn set_default_target<S: Into<String>>(&self, name: S) {
+        self.routing.write().unwrap().default_target = Some(name.into());
+    }
+
+    /// Sets whether a warning is displayed when a [`PrologueLogger`] discards a log record
+    /// because it cannot be routed to any target.
+    ///
+    /// The warning is displayed only once for each record target and it is not counted
+    /// by any target. By default, unrouted records are discarded silently.
+    ///
+    /// # Example
+    /// ```
+    /// # fn main() -> prologue_logger::error::Result<()> {
+    /// # use log::Log;
+    /// # use prologue_logger::PrologueLogger;
+    /// # use prologue_logger::capture::Capture;
+    /// let capture = Capture::new();
+    /// let logger = PrologueLogger::builder()
+    ///     .stderr(false)
+    ///     .sink(capture.clone())
+    ///     .build()?;
+    /// logger.target_list().set_warn_unrouted(true);
+    ///
+    /// let record = log::Record::builder().target("unknown").args(format_args!("disk full")).build();
+    /// logger.log(&record);
+    /// logger.log(&record);
+    ///
+    /// assert_eq!(capture.text(), "warning: log records with target `unknown` do not match any target and are discarded\n");
+    /// # Ok(()) }
+    /// ```
+    #[cfg(feature = "log")]
+    pub fn set_warn_unrouted(&self, enabled: bool) {
+        self.routing.write().unwrap().warn_unrouted = enabled;
+    }
+
+    /// Reports that an entry with the given target `name` could not be routed, if requested.
+    #[cfg(feature = "log")]
+    fn report_unrouted(&self, name: &str) -> Result<()> {
+        let report = {
+            let mut routing = self.routing.write().unwrap();
+            routing.warn_unrouted && routing.reported.insert(name.to_owned())
+        };
+        if !report {
+            return Ok(());
+        }
+        let item = Entry::new_warning(format!("log records with target `{}` do not match any target and are discarded", name)).into();
+        // Written where a new target would write, but not counted by any target.
+        match &self.sinks {
+            Some(sinks) => sinks.write(&item),
+            None => {
+                #[cfg(not(feature = "indicatif"))]
+                let sink = sink::StderrSink;
+                #[cfg(feature = "indicatif")]
+                let sink = sink::MultiProgressSink::new(self.multi_progress.clone());
+                sink.write(&item)
+            }
+        }
+    }
+
+    /// Flushes the sinks of all the targets in the list, in the order in which they were added.
+    ///
+    /// # Example
+    /// ```
+    /// # fn main() -> prologue_logger::error::Result<()> {
+    /// # use prologue_logger::{Buffering, Entry, Target, TargetList};
+    /// # use prologue_logger::capture::Capture;
+    /// let capture = Capture::new();
+    /// let target_list = TargetList::new();
+    /// for name in ["zeta", "alpha", "mu"] {
+    ///     let target = Target::with_capture(name, capture.clone());
+    ///     target.set_buffering(Buffering::Buffered);
+    ///     target_list.add_target(target)?;
+    /// }
+    /// for name in ["mu", "alpha", "zeta"] {
+    ///     Entry::new_note(format!("held by `{}`", name))
+    ///         .log_to_target(&target_list.find(name).unwrap())?;
+    /// }
+    ///
+    /// target_list.flush()?;
+    /// assert_eq!(capture.text(), "note: held by `zeta`\nnote: held by `alpha`\nnote: held by `mu`\n");
+    /// # Ok(()) }
+    /// ```
+    pub fn flush(&self) -> Result<()> {
+        let targets: Vec<Target> = self.list.read().unwrap().iter().cloned().collect();
+        targets.iter()
+            .map(Target::flush)
+            .fold(Ok(()), Result::and)
     }
 
     /// Creates a new target inside the list and outputs it.
@@ -1791,14 +3414,48 @@ impl TargetList {
     /// # Ok(()) }
     /// ```
     pub fn create_target<S: Into<Cow<'static, str>>>(&self, name: S) -> Result<Target> {
-        #[cfg(not(feature = "indicatif"))]
-        let target = Target::new(name);
-        #[cfg(feature = "indicatif")]
-        let target = Target::with_multi_progress(name, self.multi_progress.clone());
+        let target = match &self.sinks {
+            Some(sinks) => Target::with_sinks(name, sinks.detach()),
+            None => {
+                #[cfg(not(feature = "indicatif"))]
+                let target = Target::new(name);
+                #[cfg(feature = "indicatif")]
+                let target = Target::with_multi_progress(name, self.multi_progress.clone());
+                target
+            }
+        };
         self.add_target(target.clone())?;
         Ok(target)
     }
 
+    /// Creates a [child](Target::create_child) of `parent` and adds it to the list.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ErrorKind::TargetAlreadyExists`] if a target with the same name is
+    /// already in the list, in which case the child is not created.
+    ///
+    /// # Example
+    /// ```
+    /// # fn main() -> prologue_logger::error::Result<()> {
+    /// # use prologue_logger::TargetList;
+    /// let target_list = TargetList::new();
+    /// let workspace = target_list.create_target("workspace")?;
+    /// let core = target_list.create_child_target(&workspace, "workspace/core")?;
+    ///
+    /// assert!(target_list.find("workspace/core").is_some());
+    /// assert_eq!(workspace.children().len(), 1);
+    /// assert!(target_list.create_child_target(&core, "workspace").is_err());
+    /// assert!(core.children().is_empty());
+    /// # Ok(()) }
+    /// ```
+    pub fn create_child_target<S: Into<Cow<'static, str>>>(&self, parent: &Target, name: S) -> Result<Target> {
+        let child = parent.new_child(name);
+        self.add_target(child.clone())?;
+        parent.children.write().unwrap().push(child.clone());
+        Ok(child)
+    }
+
     /// Adds a previously created target inside the list.
     /// 
     /// # Example
@@ -1812,13 +3469,159 @@ impl TargetList {
     /// # Ok(()) }
     /// ```
     pub fn add_target(&self, target: Target) -> Result<()> {
-        if let Some(other_target) = self.find(target.name.as_ref()) {
-            Err(ErrorKind::TargetAlreadyExists(other_target.name.to_string()).into())
-        } else {
-            self.list.lock().unwrap()
-                .push(target);
-            Ok(())
+        // The settings of the list are applied while holding the list lock, and the setters
+        // of the list lock it before their setting, so that no target misses a new setting.
+        let mut list = self.list.write().unwrap();
+        let name = target.name.to_string();
+        if list.get(&name).is_some() {
+            return Err(ErrorKind::TargetAlreadyExists(name).into());
+        }
+        if let Some(deduplicator) = self.deduplicator.read().unwrap().as_ref() {
+            target.set_deduplicator(Some(deduplicator.clone()));
+        }
+        target.set_run_limit(self.error_limit.read().unwrap().clone());
+        if let Some(levels) = self.lint_levels.read().unwrap().as_ref() {
+            target.set_lint_levels(levels.clone());
+        }
+        if let Some(baseline) = self.baseline.read().unwrap().as_ref() {
+            target.set_baseline(Some(baseline.clone()));
+        }
+        list.insert(name, target);
+        Ok(())
+    }
+
+    /// Shares the given [`Deduplicator`] among all the targets of the list, including
+    /// the ones added later, so that an entry is hidden if any target already logged it;
+    /// `None` disables the deduplication for all the targets.
+    ///
+    /// # Example
+    /// ```
+    /// # fn main() -> prologue_logger::error::Result<()> {
+    /// # use prologue_logger::{Entry, TargetList};
+    /// # use prologue_logger::fingerprint::Deduplicator;
+    /// let target_list = TargetList::new();
+    /// target_list.set_deduplicator(Some(Deduplicator::new()));
+    /// let first = target_list.create_target("first")?;
+    /// let second = target_list.create_target("second")?;
+    ///
+    /// Entry::new_warning("unused key `edition`")
+    ///     .log_to_target(&first)?;
+    /// Entry::new_warning("unused key `edition`")
+    ///     .log_to_target(&second)?;
+    ///
+    /// assert_eq!(first.warning_count(), 1);
+    /// assert_eq!(second.warning_count(), 0);
+    /// assert_eq!(second.hidden_count(), 1);
+    /// # Ok(()) }
+    /// ```
+    pub fn set_deduplicator(&self, deduplicator: Option<Deduplicator>) {
+        let list = self.list.read().unwrap();
+        let mut current = self.deduplicator.write().unwrap();
+        for target in list.iter() {
+            target.set_deduplicator(deduplicator.clone());
+        }
+        *current = deduplicator;
+    }
+
+    /// Sets the maximum number of errors of the whole run, i.e. of all the targets
+    /// of the list together, including the ones added later; `None` removes the limit.
+    ///
+    /// The errors are counted from the moment the limit is set. When the limit is reached,
+    /// the target logging the last error writes `error: aborting due to N previous errors`
+    /// with the errors and warnings of the whole run, and runs its [`on_abort`](Target::on_abort)
+    /// callback, if any; then every target of the list discards the entries logged to it.
+    /// This is independent of the [error limit](Target::set_error_limit) of each target.
+    ///
+    /// # Example
+    /// ```
+    /// # fn main() -> prologue_logger::error::Result<()> {
+    /// # use prologue_logger::{Entry, Target, TargetList};
+    /// # use prologue_logger::capture::Capture;
+    /// let capture = Capture::new();
+    /// let target_list = TargetList::new();
+    /// target_list.set_error_limit(Some(3));
+    /// let parser = Target::with_capture("parser", capture.clone());
+    /// let checker = Target::with_capture("checker", capture.clone());
+    /// target_list.add_target(parser.clone())?;
+    /// target_list.add_target(checker.clone())?;
+    ///
+    /// Entry::new_warning("unused key `edition`")
+    ///     .log_to_target(&parser)?;
+    /// for line in 1..=10 {
+    ///     let target = if line % 2 == 0 { &checker } else { &parser };
+    ///     Entry::new_error(format!("invalid value on line {}", line))
+    ///         .log_to_target(target)?;
+    /// }
+    ///
+    /// assert!(target_list.is_aborted());
+    /// assert_eq!((parser.error_count(), checker.error_count()), (2, 1));
+    /// assert_eq!(capture.text(), concat!(
+    ///     "warning: unused key `edition`\n",
+    ///     "error: invalid value on line 1\n",
+    ///     "error: invalid value on line 2\n",
+    ///     "error: invalid value on line 3\n",
+    ///     "error: aborting due to 3 previous errors; 1 warning emitted\n"
+    /// ));
+    /// # Ok(()) }
+    /// ```
+    pub fn set_error_limit(&self, limit: Option<usize>) {
+        let run_limit = limit.map(|limit| Arc::new(RunLimit::new(limit)));
+        let list = self.list.read().unwrap();
+        let mut current = self.error_limit.write().unwrap();
+        for target in list.iter() {
+            target.set_run_limit(run_limit.clone());
+        }
+        *current = run_limit;
+    }
+
+    /// Sets the same [lint levels](Target::set_lint_levels) for all the targets of the list,
+    /// including the ones added later.
+    ///
+    /// # Example
+    /// ```
+    /// # fn main() -> prologue_logger::error::Result<()> {
+    /// # use prologue_logger::{declare_lint, TargetList};
+    /// # use prologue_logger::lint::{LintLevels, LintRegistry};
+    /// declare_lint!(UNUSED_KEY, Warn, "detects keys which are not used by any setting");
+    /// let registry = LintRegistry::new().register(&UNUSED_KEY);
+    ///
+    /// let target_list = TargetList::new();
+    /// target_list.set_lint_levels(LintLevels::parse("-D unused-key")?);
+    /// let target = target_list.create_target("my-target")?;
+    ///
+    /// registry.entry(&UNUSED_KEY, "unused key `edition`")?
+    ///     .log_to_target(&target)?;
+    ///
+    /// assert_eq!(target.warning_count(), 0);
+    /// assert_eq!(target.error_count(), 1);
+    /// # Ok(()) }
+    /// ```
+    pub fn set_lint_levels(&self, levels: LintLevels) {
+        let list = self.list.read().unwrap();
+        let mut current = self.lint_levels.write().unwrap();
+        for target in list.iter() {
+            target.set_lint_levels(levels.clone());
+        }
+        *current = Some(levels);
+    }
+
+    /// Shares the given [`Baseline`] among all the targets of the list, including the ones
+    /// added later; `None` disables the baseline for all the targets.
+    pub fn set_baseline(&self, baseline: Option<Baseline>) {
+        let list = self.list.read().unwrap();
+        let mut current = self.baseline.write().unwrap();
+        for target in list.iter() {
+            target.set_baseline(baseline.clone());
         }
+        *current = baseline;
+    }
+
+    /// Returns `true` if any target of the list reached its [error limit](Target::set_error_limit),
+    /// or the list reached [its own](TargetList::set_error_limit).
+    pub fn is_aborted(&self) -> bool {
+        let run_aborted = self.error_limit.read().unwrap().as_ref()
+            .is_some_and(|run| run.aborted.load(atomic::Ordering::Relaxed));
+        run_aborted || self.list.read().unwrap().iter().any(Target::is_aborted)
     }
 
     /// Clears all the attached progress bars.
@@ -1838,24 +3641,154 @@ impl TargetList {
     }
 }
 
+/// Guard returned by [`PrologueLogger::init`] and [`PrologueLoggerBuilder::try_init`],
+/// which flushes all the targets when dropped.
+///
+/// The guard dereferences to the [`TargetList`] of the logger; keep it alive until
+/// the end of `main` so that the last entries are not lost.
+/// Since [`std::process::exit`] does not run the destructors, exit through
+/// [`FlushGuard::exit`] instead, which flushes the targets first.
+///
+/// If the logger writes the entries [from a dedicated thread](PrologueLoggerBuilder::background),
+/// the guard also stops the writer thread and waits for it, once the targets are flushed.
+///
+/// # Example
+/// ```
+/// # use prologue_logger::{Entry, PrologueLogger};
+/// fn main() -> prologue_logger::error::Result<()> {
+///     let target_list = PrologueLogger::builder().try_init()?;
+///     target_list.create_target("my-target")?;
+///
+///     Entry::new_note("this entry is flushed when `target_list` is dropped")
+///         .log("my-target");
+///
+///     Ok(())
+/// }
+/// ```
+#[cfg(feature = "log")]
+#[derive(Debug)]
+#[must_use = "the targets are flushed as soon as the guard is dropped"]
+pub struct FlushGuard {
+    target_list: TargetList,
+    background: Option<BackgroundHandle>
+}
+#[cfg(feature = "log")]
+impl Deref for FlushGuard {
+    type Target = TargetList;
+
+    fn deref(&self) -> &TargetList {
+        &self.target_list
+    }
+}
+#[cfg(feature = "log")]
+impl Drop for FlushGuard {
+    fn drop(&mut self) {
+        // There is no way to report the errors.
+        let _ = self.target_list.flush();
+        if let Some(background) = &self.background {
+            let _ = background.join();
+        }
+    }
+}
+#[cfg(feature = "log")]
+impl FlushGuard {
+    /// Returns the handle of the writer thread, if the logger writes the entries
+    /// [from a dedicated thread](PrologueLoggerBuilder::background).
+    pub fn background_handle(&self) -> Option<&BackgroundHandle> {
+        self.background.as_ref()
+    }
+
+    /// Flushes the targets, as when the guard is dropped, and then terminates the process
+    /// with the given exit `code`, as [`std::process::exit`] does.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use prologue_logger::{Entry, PrologueLogger};
+    /// let target_list = PrologueLogger::init().unwrap();
+    /// let target = target_list.create_target("my-target").unwrap();
+    ///
+    /// Entry::new_error("could not compile `my-target`")
+    ///     .log("my-target");
+    ///
+    /// if target.error_count() > 0 {
+    ///     // The error above is flushed before exiting.
+    ///     target_list.exit(101);
+    /// }
+    /// ```
+    pub fn exit(self, code: i32) -> ! {
+        drop(self);
+        std::process::exit(code)
+    }
+}
+
+/// Targets of a [`TargetList`] in the order in which they were added, indexed by name.
+#[derive(Debug, Default)]
+struct Targets {
+    targets: Vec<Target>,
+    index: HashMap<String, usize>
+}
+impl Targets {
+    fn get(&self, name: &str) -> Option<&Target> {
+        self.index.get(name).map(|&index| &self.targets[index])
+    }
+
+    fn insert(&mut self, name: String, target: Target) {
+        self.index.insert(name, self.targets.len());
+        self.targets.push(target);
+    }
+
+    fn iter(&self) -> std::slice::Iter<'_, Target> {
+        self.targets.iter()
+    }
+}
+
+/// Rules to route the log records to the targets of a [`TargetList`].
+#[derive(Debug, Default)]
+struct Routing {
+    default_target: Option<String>,
+    #[cfg(feature = "log")]
+    warn_unrouted: bool,
+    #[cfg(feature = "log")]
+    reported: HashSet<String>
+}
+
 /// The `prologue` logger `struct`.
 ///
 /// It handles log entries and displays them to `stderr`.
 #[derive(Debug)]
 pub struct PrologueLogger {
-    target_list: TargetList
+    target_list: TargetList,
+    #[cfg(feature = "log")]
+    filter: Filter,
+    #[cfg(feature = "log")]
+    background: Option<BackgroundHandle>
+}
+impl Default for PrologueLogger {
+    fn default() -> Self {
+        PrologueLogger {
+            target_list: TargetList::new(),
+            #[cfg(feature = "log")]
+            filter: Filter::new(),
+            #[cfg(feature = "log")]
+            background: None
+        }
+    }
 }
 impl PrologueLogger {
     /// Creates a new `PrologueLogger` with an empty target list.
     pub fn new() -> PrologueLogger {
-        #[cfg(feature = "indicatif")]
-        let multi_progress = indicatif::MultiProgress::new();
-        PrologueLogger {
-            target_list: TargetList { list: Arc::new(Mutex::new(Vec::new())), #[cfg(feature = "indicatif")] multi_progress }
-        }
+        Default::default()
+    }
+
+    /// Creates a new `PrologueLogger` with an empty target list and the given level `filter`.
+    #[cfg(feature = "log")]
+    pub fn with_filter(filter: Filter) -> PrologueLogger {
+        PrologueLogger { filter, ..Default::default() }
     }
 
-    /// Initializes the `PrologueLogger` as the main logger with crate [`log`].
+    /// Initializes the `PrologueLogger` as the main logger with crate [`log`],
+    /// and returns a [`FlushGuard`] which dereferences to its [`TargetList`]
+    /// and flushes the targets when dropped.
     ///
     /// # Example
     /// ```
@@ -1883,13 +3816,91 @@ impl PrologueLogger {
     ///     Ok(())
     /// }
     /// ```
+    ///
+    /// The logger uses the [default filter](Filter::default);
+    /// to use a different filter, see [`init_with_filter`](PrologueLogger::init_with_filter).
+    ///
+    /// To configure the logger further, see [`builder`](PrologueLogger::builder).
+    #[cfg(feature = "log")]
+    pub fn init() -> Result<FlushGuard> {
+        PrologueLogger::init_with_filter(Filter::default())
+    }
+
+    /// Initializes the `PrologueLogger` as the main logger with crate [`log`],
+    /// logging only the records allowed by the given level `filter`, and returns
+    /// a [`FlushGuard`] as [`init`](PrologueLogger::init) does.
+    ///
+    /// # Example
+    /// ```
+    /// # use prologue_logger::{Entry, PrologueLogger};
+    /// # use prologue_logger::filter::Filter;
+    /// fn main() -> prologue_logger::error::Result<()> {
+    ///     // Read the filter from the environment, e.g. `RUST_LOG=my-target=warn`.
+    ///     let filter = Filter::from_env("RUST_LOG")?;
+    ///     // Initialize the logger.
+    ///     let target_list = PrologueLogger::init_with_filter(filter)?;
+    ///     target_list.create_target("my-target")?;
+    ///
+    ///     Entry::new_note("this note is logged only if notes are enabled for `my-target`")
+    ///         .log("my-target");
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "log")]
+    pub fn init_with_filter(filter: Filter) -> Result<FlushGuard> {
+        PrologueLogger::with_filter(filter).install()
+    }
+
+    /// Returns a [`PrologueLoggerBuilder`] to configure the logger before creating it.
+    ///
+    /// # Example
+    /// ```
+    /// # use log::LevelFilter;
+    /// # use prologue_logger::{Entry, PrologueLogger};
+    /// # use prologue_logger::builder::ColorChoice;
+    /// fn main() -> prologue_logger::error::Result<()> {
+    ///     let target_list = PrologueLogger::builder()
+    ///         .level(LevelFilter::Info)
+    ///         .color(ColorChoice::Never)
+    ///         .target("my-target")
+    ///         // Let the end users override the settings, e.g. with `PROLOGUE_COLOR=always`.
+    ///         .from_env()?
+    ///         .try_init()?;
+    ///
+    ///     Entry::new_note("the logger is now available")
+    ///         .log("my-target");
+    ///     assert!(target_list.find("my-target").is_some());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "log")]
+    pub fn builder() -> PrologueLoggerBuilder {
+        PrologueLoggerBuilder::new()
+    }
+
+    /// Installs `self` as the main logger with crate [`log`].
     #[cfg(feature = "log")]
-    pub fn init() -> Result<TargetList> {
-        let logger = PrologueLogger::new();
-        let target_list = logger.target_list();
-        log::set_max_level(log::LevelFilter::Debug);
-        log::set_boxed_logger(Box::new(logger))?;
-        Ok(target_list)
+    fn install(self) -> Result<FlushGuard> {
+        let target_list = self.target_list();
+        let background = self.background.clone();
+        log::set_max_level(self.filter.max_level());
+        log::set_boxed_logger(Box::new(self))?;
+        Ok(FlushGuard { target_list, background })
+    }
+
+    /// Returns the level filter of the logger.
+    #[cfg(feature = "log")]
+    pub fn filter(&self) -> &Filter {
+        &self.filter
+    }
+
+    /// Returns the handle of the writer thread, if the logger writes the entries
+    /// [from a dedicated thread](PrologueLoggerBuilder::background).
+    #[cfg(feature = "log")]
+    pub fn background_handle(&self) -> Option<&BackgroundHandle> {
+        self.background.as_ref()
     }
 
     /// Finds a target inside the target list.
@@ -1978,17 +3989,20 @@ impl PrologueLogger {
 #[cfg(feature = "log")]
 impl log::Log for PrologueLogger {
     fn enabled(&self, metadata: &Metadata) -> bool {
-        metadata.level() < LevelFilter::Debug
+        self.filter.enabled(metadata.target(), metadata.level())
     }
 
     fn log(&self, record: &Record) {
         if self.enabled(record.metadata()) {
-            if let Some(target) = self.find_target(record.target()) {
-                target.log_record(record)
-                    .expect("the logger encountered an `io` error and could not continue");
-            }
+            match self.target_list.route(record.target()) {
+                Some(target) => target.log_record(record),
+                None => self.target_list.report_unrouted(record.target())
+            }.expect("the logger encountered an `io` error and could not continue");
         }
     }
 
-    fn flush(&self) {}
+    fn flush(&self) {
+        // There is no way to report the error.
+        let _ = self.target_list.flush();
+    }
 }
\ No newline at end of file
@@ -0,0 +1,606 @@
+//! Lints and their levels, in the style of `rustc`.
+//!
+//! A [`Lint`] is a named check with a default [`Level`], usually declared with the
+//! [`declare_lint!`](crate::declare_lint) macro, optionally belonging to a group of lints.
+//! Lints are collected into a [`LintRegistry`], which creates the entries reporting them
+//! and lists them all in the style of `rustc -W help`.
+//!
+//! Entries reporting a lint get their severity from the [`LintLevels`] of the target
+//! they are logged to:
+//! * `allow` suppresses the entry;
+//! * `warn` makes it a warning;
+//! * `deny` and `forbid` make it an error, where `forbid` also prevents any later override.
+//!
+//! Lint levels are usually given on the command line with `rustc`-style flags,
+//! e.g. `-A unused_imports -D warnings`, where `warnings` stands for all the lints
+//! which would otherwise be warnings. Entries with a source also get a footer note
+//! explaining where the level comes from, e.g. ``= note: `#[warn(unused_imports)]` on by default``.
+//!
+//! # Example
+//! ```
+//! # fn main() -> prologue_logger::error::Result<()> {
+//! # use prologue_logger::{declare_lint, Target};
+//! # use prologue_logger::capture::Capture;
+//! # use prologue_logger::lint::{LintLevels, LintRegistry};
+//! declare_lint! {
+//!     /// Detects keys which are not used by any setting.
+//!     pub UNUSED_KEY, Warn, "detects keys which are not used by any setting", group = "unused"
+//! }
+//!
+//! let registry = LintRegistry::new().register(&UNUSED_KEY);
+//! let capture = Capture::new();
+//! let target = Target::with_capture("my-target", capture.clone());
+//! target.set_lint_levels(LintLevels::parse("-D warnings")?);
+//!
+//! registry.entry(&UNUSED_KEY, "unused key `edition`")?
+//!     .named_source("Config.toml", 3, 1)
+//!     .new_line(3, "edition = 2021")
+//!     .annotate_warn(1, 7, "")?
+//!     .finish()
+//!     .log_to_target(&target)?;
+//!
+//! assert_eq!(target.error_count(), 1);
+//! assert_eq!(capture.text(), concat!(
+//!     "error: unused key `edition`\n",
+//!     " --> Config.toml:3:1\n",
+//!     "  |\n",
+//!     "3 | edition = 2021\n",
+//!     "  | ^^^^^^^ \n",
+//!     "  |\n",
+//!     "  = note: `-D unused-key` implied by `-D warnings`\n",
+//!     "\n"
+//! ));
+//! # Ok(()) }
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter, Write};
+use std::str::FromStr;
+
+use crate::{Entry, EntryKind};
+use crate::error::{Error, ErrorKind, Result};
+
+/// Name of the pseudo-lint standing for all the lints which would otherwise be warnings.
+pub const WARNINGS: &str = "warnings";
+
+/// Level of a lint.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "lowercase"))]
+pub enum Level {
+    /// The lint is not reported.
+    Allow,
+    /// The lint is reported as a warning.
+    Warn,
+    /// The lint is reported as an error.
+    Deny,
+    /// The lint is reported as an error, and its level cannot be changed anymore.
+    Forbid
+}
+impl Display for Level {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Level::Allow => "allow",
+            Level::Warn => "warn",
+            Level::Deny => "deny",
+            Level::Forbid => "forbid"
+        })
+    }
+}
+impl Level {
+    /// Returns the letter of the command line flag setting this level, e.g. `D` for `-D`.
+    pub fn flag(&self) -> char {
+        match self {
+            Level::Allow => 'A',
+            Level::Warn => 'W',
+            Level::Deny => 'D',
+            Level::Forbid => 'F'
+        }
+    }
+
+    /// Returns the kind of the entries reported with this level, or `None` if they are suppressed.
+    pub fn entry_kind(&self) -> Option<EntryKind> {
+        match self {
+            Level::Allow => None,
+            Level::Warn => Some(EntryKind::Warning),
+            Level::Deny | Level::Forbid => Some(EntryKind::Error)
+        }
+    }
+
+    /// Parses a command line flag, e.g. `-A` or `--allow`.
+    fn from_flag(flag: &str) -> Option<Level> {
+        match flag {
+            "-A" | "--allow" => Some(Level::Allow),
+            "-W" | "--warn" => Some(Level::Warn),
+            "-D" | "--deny" => Some(Level::Deny),
+            "-F" | "--forbid" => Some(Level::Forbid),
+            _ => None
+        }
+    }
+}
+
+/// A named check, whose entries are reported according to its [`Level`].
+///
+/// Lints are usually declared as `static` items with the [`declare_lint!`](crate::declare_lint)
+/// macro, or directly, e.g.
+/// ```
+/// # use prologue_logger::lint::{Level, Lint};
+/// static UNUSED_KEY: Lint = Lint::new("unused_key", Level::Warn)
+///     .with_group("unused")
+///     .with_description("detects keys which are not used by any setting");
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub struct Lint {
+    name: &'static str,
+    default_level: Level,
+    group: Option<&'static str>,
+    description: &'static str
+}
+impl Lint {
+    /// Creates a new lint with the given `name`, which should be in `snake_case`,
+    /// and the given `default_level`.
+    pub const fn new(name: &'static str, default_level: Level) -> Lint {
+        Lint { name, default_level, group: None, description: "" }
+    }
+
+    /// Adds the lint to the group called `group`, which should be in `snake_case`.
+    ///
+    /// The level of all the lints of a group can be set at once by its name, e.g. `-A unused`.
+    pub const fn with_group(mut self, group: &'static str) -> Lint {
+        self.group = Some(group);
+        self
+    }
+
+    /// Sets the description of the lint, displayed by [`LintRegistry::help`].
+    pub const fn with_description(mut self, description: &'static str) -> Lint {
+        self.description = description;
+        self
+    }
+
+    /// Returns the name of the lint.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the level of the lint when no other level is given.
+    pub fn default_level(&self) -> Level {
+        self.default_level
+    }
+
+    /// Returns the group of the lint, if any.
+    pub fn group(&self) -> Option<&'static str> {
+        self.group
+    }
+
+    /// Returns the description of the lint.
+    pub fn description(&self) -> &'static str {
+        self.description
+    }
+}
+
+/// Declares a `static` [`Lint`], in the style of the `declare_lint!` macro of `rustc`.
+///
+/// The name of the lint is the name of the `static` item in lowercase, the level is
+/// one of the variants of [`Level`], and the description is a string literal; a group can
+/// follow as `group = "name"`.
+///
+/// # Example
+/// ```
+/// # use prologue_logger::declare_lint;
+/// # use prologue_logger::lint::Level;
+/// declare_lint! {
+///     /// Detects keys which are not used by any setting.
+///     pub UNUSED_KEY, Warn, "detects keys which are not used by any setting", group = "unused"
+/// }
+/// declare_lint!(INVALID_VALUE, Deny, "detects values of the wrong type");
+///
+/// assert_eq!(UNUSED_KEY.name(), "unused_key");
+/// assert_eq!(UNUSED_KEY.group(), Some("unused"));
+/// assert_eq!(INVALID_VALUE.default_level(), Level::Deny);
+/// ```
+#[macro_export]
+macro_rules! declare_lint {
+    ($(#[$attr:meta])* $vis:vis $name:ident, $level:ident, $description:literal $(, group = $group:literal)? $(,)?) => {
+        $(#[$attr])*
+        $vis static $name: $crate::lint::Lint = {
+            const BYTES: [u8; stringify!($name).len()] = $crate::lint::__lowercase(stringify!($name));
+            const NAME: &str = $crate::lint::__as_str(&BYTES);
+            $crate::lint::Lint::new(NAME, $crate::lint::Level::$level)
+                .with_description($description)
+                $(.with_group($group))?
+        };
+    };
+}
+
+/// Converts an ASCII name to lowercase at compile time, for [`declare_lint!`](crate::declare_lint).
+#[doc(hidden)]
+pub const fn __lowercase<const N: usize>(name: &str) -> [u8; N] {
+    let name = name.as_bytes();
+    let mut bytes = [0; N];
+    let mut i = 0;
+    while i < N {
+        bytes[i] = name[i].to_ascii_lowercase();
+        i += 1;
+    }
+    bytes
+}
+
+/// Converts the output of [`__lowercase`] back to a string, for [`declare_lint!`](crate::declare_lint).
+#[doc(hidden)]
+pub const fn __as_str(bytes: &[u8]) -> &str {
+    match std::str::from_utf8(bytes) {
+        Ok(name) => name,
+        Err(_) => panic!("lint names must be valid UTF-8")
+    }
+}
+
+/// Reference to a [`Lint`] stored inside an entry.
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct LintRef {
+    pub(crate) name: String,
+    #[cfg_attr(feature = "serde", serde(rename = "default"))]
+    pub(crate) default_level: Level,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub(crate) group: Option<String>
+}
+impl From<&Lint> for LintRef {
+    fn from(lint: &Lint) -> Self {
+        LintRef {
+            name: lint.name.to_owned(),
+            default_level: lint.default_level,
+            group: lint.group.map(str::to_owned)
+        }
+    }
+}
+
+/// Set of the lints known to an application.
+///
+/// Entries reporting a lint can only be created through the registry of the lint;
+/// see [`entry`](LintRegistry::entry).
+#[derive(Clone, Debug, Default)]
+pub struct LintRegistry {
+    lints: BTreeMap<&'static str, &'static Lint>
+}
+impl LintRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> LintRegistry {
+        Default::default()
+    }
+
+    /// Adds `lint` to the registry.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a different lint with the same name is already registered.
+    pub fn register(mut self, lint: &'static Lint) -> Self {
+        if let Some(registered) = self.lints.insert(lint.name, lint) {
+            assert_eq!(registered, lint, "duplicate specification of lint `{}`", lint.name);
+        }
+        self
+    }
+
+    /// Returns the registered lint called `name`, if any.
+    ///
+    /// The name is compared ignoring the difference between `-` and `_`.
+    pub fn find(&self, name: &str) -> Option<&'static Lint> {
+        self.lints.get(normalize(name).as_str()).copied()
+    }
+
+    /// Returns all the registered lints, sorted by name.
+    pub fn lints(&self) -> impl Iterator<Item = &'static Lint> + '_ {
+        self.lints.values().copied()
+    }
+
+    /// Returns all the groups of the registered lints, sorted by name, each with its lints.
+    pub fn groups(&self) -> BTreeMap<&'static str, Vec<&'static Lint>> {
+        let mut groups = BTreeMap::<_, Vec<_>>::new();
+        for lint in self.lints() {
+            if let Some(group) = lint.group {
+                groups.entry(group).or_default().push(lint);
+            }
+        }
+        groups
+    }
+
+    /// Creates an entry reporting `lint`, whose kind is given by the default level of the lint
+    /// until the entry is logged to a target with its [`LintLevels`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ErrorKind::UnknownLint`] if `lint` is not registered.
+    ///
+    /// # Example
+    /// ```
+    /// # use prologue_logger::{declare_lint, EntryKind};
+    /// # use prologue_logger::lint::LintRegistry;
+    /// declare_lint!(UNUSED_KEY, Warn, "detects keys which are not used by any setting");
+    /// declare_lint!(INVALID_VALUE, Deny, "detects values of the wrong type");
+    /// let registry = LintRegistry::new().register(&UNUSED_KEY);
+    ///
+    /// let entry = registry.entry(&UNUSED_KEY, "unused key `edition`").unwrap();
+    /// assert_eq!(entry.kind(), EntryKind::Warning);
+    /// assert_eq!(entry.lint(), Some("unused_key"));
+    /// assert!(registry.entry(&INVALID_VALUE, "expected a string, found `42`").is_err());
+    /// ```
+    pub fn entry<S: Into<String>>(&self, lint: &Lint, message: S) -> Result<Entry> {
+        match self.lints.get(lint.name) {
+            Some(registered) if *registered == lint => {
+                let kind = lint.default_level.entry_kind().unwrap_or(EntryKind::Warning);
+                let mut entry = Entry::new(kind, message);
+                entry.lint = Some(lint.into());
+                Ok(entry)
+            },
+            _ => Err(ErrorKind::UnknownLint(lint.name.to_owned()).into())
+        }
+    }
+
+    /// Checks that every name in `levels` is a registered lint, a group or [`WARNINGS`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ErrorKind::UnknownLint`] with the first unknown name.
+    ///
+    /// # Example
+    /// ```
+    /// # fn main() -> prologue_logger::error::Result<()> {
+    /// # use prologue_logger::declare_lint;
+    /// # use prologue_logger::lint::{LintLevels, LintRegistry};
+    /// declare_lint!(UNUSED_KEY, Warn, "detects keys which are not used by any setting", group = "unused");
+    /// let registry = LintRegistry::new().register(&UNUSED_KEY);
+    ///
+    /// assert!(registry.validate(&LintLevels::parse("-A unused -D warnings")?).is_ok());
+    /// assert!(registry.validate(&LintLevels::parse("-A unused-keys")?).is_err());
+    /// # Ok(()) }
+    /// ```
+    pub fn validate(&self, levels: &LintLevels) -> Result<()> {
+        let groups = self.groups();
+        match levels.levels.iter().find(|(name, _)| {
+            name != WARNINGS && !self.lints.contains_key(name.as_str()) && !groups.contains_key(name.as_str())
+        }) {
+            Some((name, _)) => Err(ErrorKind::UnknownLint(dashed(name)).into()),
+            None => Ok(())
+        }
+    }
+
+    /// Returns a table of all the registered lints and groups, in the style of `rustc -W help`.
+    ///
+    /// # Example
+    /// ```
+    /// # use prologue_logger::declare_lint;
+    /// # use prologue_logger::lint::LintRegistry;
+    /// declare_lint!(UNUSED_KEY, Warn, "detects keys which are not used by any setting", group = "unused");
+    /// declare_lint!(UNUSED_SECTION, Allow, "detects empty sections", group = "unused");
+    /// declare_lint!(INVALID_VALUE, Deny, "detects values of the wrong type");
+    /// let registry = LintRegistry::new()
+    ///     .register(&UNUSED_KEY)
+    ///     .register(&UNUSED_SECTION)
+    ///     .register(&INVALID_VALUE);
+    ///
+    /// assert_eq!(registry.help(), concat!(
+    ///     "Available lint options:\n",
+    ///     "    -W <foo>           Warn about <foo>\n",
+    ///     "    -A <foo>           Allow <foo>\n",
+    ///     "    -D <foo>           Deny <foo>\n",
+    ///     "    -F <foo>           Forbid <foo> (deny <foo> and all attempts to override)\n",
+    ///     "\n",
+    ///     "Lint checks:\n",
+    ///     "\n",
+    ///     "            name  default  meaning\n",
+    ///     "            ----  -------  -------\n",
+    ///     "   invalid-value  deny     detects values of the wrong type\n",
+    ///     "      unused-key  warn     detects keys which are not used by any setting\n",
+    ///     "  unused-section  allow    detects empty sections\n",
+    ///     "\n",
+    ///     "Lint groups:\n",
+    ///     "\n",
+    ///     "      name  sub-lints\n",
+    ///     "      ----  ---------\n",
+    ///     "  warnings  all lints that are set to issue warnings\n",
+    ///     "    unused  unused-key, unused-section\n"
+    /// ));
+    /// ```
+    pub fn help(&self) -> String {
+        let mut help = String::from(concat!(
+            "Available lint options:\n",
+            "    -W <foo>           Warn about <foo>\n",
+            "    -A <foo>           Allow <foo>\n",
+            "    -D <foo>           Deny <foo>\n",
+            "    -F <foo>           Forbid <foo> (deny <foo> and all attempts to override)\n",
+            "\n"
+        ));
+        let width = self.lints.keys().map(|name| name.len()).max().unwrap_or(0).max("name".len());
+        help.push_str("Lint checks:\n\n");
+        let _ = writeln!(help, "  {:>width$}  {:7}  meaning", "name", "default", width = width);
+        let _ = writeln!(help, "  {:>width$}  {:7}  -------", "----", "-------", width = width);
+        for lint in self.lints() {
+            let _ = writeln!(help, "  {:>width$}  {:7}  {}", dashed(lint.name), lint.default_level.to_string(), lint.description, width = width);
+        }
+        let groups = self.groups();
+        let width = groups.keys().map(|name| name.len()).max().unwrap_or(0).max(WARNINGS.len());
+        help.push_str("\nLint groups:\n\n");
+        let _ = writeln!(help, "  {:>width$}  sub-lints", "name", width = width);
+        let _ = writeln!(help, "  {:>width$}  ---------", "----", width = width);
+        let _ = writeln!(help, "  {:>width$}  all lints that are set to issue warnings", WARNINGS, width = width);
+        for (group, lints) in groups {
+            let lints = lints.iter().map(|lint| dashed(lint.name)).collect::<Vec<_>>();
+            let _ = writeln!(help, "  {:>width$}  {}", dashed(group), lints.join(", "), width = width);
+        }
+        help
+    }
+}
+
+/// Level of a lint, resolved by [`LintLevels`].
+pub(crate) struct Resolved {
+    pub(crate) level: Level,
+    /// Note explaining where the level comes from, e.g. `` `#[warn(unused)]` on by default ``.
+    pub(crate) note: String
+}
+
+/// Levels of the lints, set by name in order, as with the flags of `rustc`.
+///
+/// A later level replaces an earlier one for the same lint, unless the earlier one is
+/// [`Forbid`](Level::Forbid). The name of a group sets the level of all its lints, and the
+/// name [`WARNINGS`] sets the level of all the lints which would otherwise be warnings. Names are compared ignoring the difference between `-` and `_`.
+///
+/// # Example
+/// ```
+/// # fn main() -> prologue_logger::error::Result<()> {
+/// # use prologue_logger::declare_lint;
+/// # use prologue_logger::lint::{Level, LintLevels};
+/// declare_lint!(UNUSED_KEY, Warn, "detects keys which are not used by any setting", group = "unused");
+/// declare_lint!(UNUSED_SECTION, Warn, "detects empty sections", group = "unused");
+/// declare_lint!(UNKNOWN_KEY, Deny, "detects keys which are not known");
+///
+/// let levels: LintLevels = "-F unused-key -A unused -W unknown-key -D warnings".parse()?;
+/// // `forbid` cannot be overridden, not even by a group.
+/// assert_eq!(levels.level(&UNUSED_KEY), Level::Forbid);
+/// assert_eq!(levels.level(&UNUSED_SECTION), Level::Allow);
+/// // `warnings` applies to the lints which would otherwise be warnings.
+/// assert_eq!(levels.level(&UNKNOWN_KEY), Level::Deny);
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Eq, PartialEq, Debug, Hash, Default)]
+pub struct LintLevels {
+    levels: Vec<(String, Level)>
+}
+impl FromStr for LintLevels {
+    type Err = Error;
+
+    fn from_str(flags: &str) -> Result<LintLevels> {
+        LintLevels::parse(flags)
+    }
+}
+impl LintLevels {
+    /// Creates an empty set of levels, i.e. every lint has its default level.
+    pub fn new() -> LintLevels {
+        Default::default()
+    }
+
+    /// Sets the `level` of the lint called `name`.
+    pub fn set<S: AsRef<str>>(mut self, name: S, level: Level) -> Self {
+        self.levels.push((normalize(name.as_ref()), level));
+        self
+    }
+
+    /// Sets the lint called `name` to [`Allow`](Level::Allow), as with `-A name`.
+    pub fn allow<S: AsRef<str>>(self, name: S) -> Self {
+        self.set(name, Level::Allow)
+    }
+
+    /// Sets the lint called `name` to [`Warn`](Level::Warn), as with `-W name`.
+    pub fn warn<S: AsRef<str>>(self, name: S) -> Self {
+        self.set(name, Level::Warn)
+    }
+
+    /// Sets the lint called `name` to [`Deny`](Level::Deny), as with `-D name`.
+    pub fn deny<S: AsRef<str>>(self, name: S) -> Self {
+        self.set(name, Level::Deny)
+    }
+
+    /// Sets the lint called `name` to [`Forbid`](Level::Forbid), as with `-F name`.
+    pub fn forbid<S: AsRef<str>>(self, name: S) -> Self {
+        self.set(name, Level::Forbid)
+    }
+
+    /// Parses the lint flags contained in the command line `args`.
+    ///
+    /// Flags can be written as `-A name`, `-Aname`, `--allow name` or `--allow=name`,
+    /// and similarly for `-W` (`--warn`), `-D` (`--deny`) and `-F` (`--forbid`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ErrorKind::InvalidLintFlag`] if some argument is not a lint flag
+    /// or a flag is not followed by a lint name.
+    ///
+    /// # Example
+    /// ```
+    /// # fn main() -> prologue_logger::error::Result<()> {
+    /// # use prologue_logger::lint::LintLevels;
+    /// let levels = LintLevels::from_args(["-A", "unused", "--deny=warnings"])?;
+    /// assert_eq!(levels, LintLevels::new().allow("unused").deny("warnings"));
+    ///
+    /// assert!(LintLevels::from_args(["-X", "unused"]).is_err());
+    /// assert!(LintLevels::from_args(["-D"]).is_err());
+    /// # Ok(()) }
+    /// ```
+    pub fn from_args<I: IntoIterator<Item = S>, S: AsRef<str>>(args: I) -> Result<LintLevels> {
+        let mut levels = LintLevels::new();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let arg = arg.as_ref();
+            let invalid = || -> Error { ErrorKind::InvalidLintFlag(arg.to_owned()).into() };
+            let (level, name) = if let Some(level) = Level::from_flag(arg) {
+                let name = args.next().ok_or_else(invalid)?;
+                (level, name.as_ref().to_owned())
+            } else if let Some((flag, name)) = arg.split_once('=').filter(|(flag, _)| flag.starts_with("--")) {
+                (Level::from_flag(flag).ok_or_else(invalid)?, name.to_owned())
+            } else if arg.len() > 2 && !arg.starts_with("--") && arg.is_char_boundary(2) {
+                (Level::from_flag(&arg[..2]).ok_or_else(invalid)?, arg[2..].to_owned())
+            } else {
+                return Err(invalid());
+            };
+            if name.is_empty() || name.starts_with('-') {
+                return Err(invalid());
+            }
+            levels = levels.set(name, level);
+        }
+        Ok(levels)
+    }
+
+    /// Parses the lint flags contained in `flags`, separated by whitespace,
+    /// e.g. `-A unused -D warnings`; see [`from_args`](LintLevels::from_args).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ErrorKind::InvalidLintFlag`] if some argument is not a lint flag
+    /// or a flag is not followed by a lint name.
+    pub fn parse(flags: &str) -> Result<LintLevels> {
+        LintLevels::from_args(flags.split_whitespace())
+    }
+
+    /// Returns the level of `lint`.
+    pub fn level(&self, lint: &Lint) -> Level {
+        self.resolve(&LintRef::from(lint)).level
+    }
+
+    /// Resolves the level of a lint and explains it.
+    pub(crate) fn resolve(&self, lint: &LintRef) -> Resolved {
+        let name = normalize(&lint.name);
+        let group = lint.group.as_deref().map(normalize);
+        let mut level = lint.default_level;
+        let mut note = format!("`#[{}({})]` on by default", level, name);
+        for (rule, rule_level) in self.levels.iter() {
+            if level == Level::Forbid {
+                break;
+            }
+            if *rule == name {
+                level = *rule_level;
+                note = format!("requested on the command line with `-{} {}`", level.flag(), dashed(&name));
+            } else if Some(rule) == group.as_ref() {
+                level = *rule_level;
+                note = format!("`-{} {}` implied by `-{} {}`", level.flag(), dashed(&name), level.flag(), dashed(rule));
+            }
+        }
+        if level == Level::Warn {
+            let warnings = self.levels.iter()
+                .filter(|(rule, _)| rule == WARNINGS)
+                .map(|(_, rule)| *rule)
+                .reduce(|current, rule| if current == Level::Forbid { current } else { rule });
+            if let Some(rule) = warnings {
+                level = rule;
+                note = format!("`-{} {}` implied by `-{} {}`", level.flag(), dashed(&name), level.flag(), WARNINGS);
+            }
+        }
+        Resolved { level, note }
+    }
+}
+
+/// Normalizes the name of a lint to `snake_case`.
+fn normalize(name: &str) -> String {
+    name.replace('-', "_")
+}
+
+/// Returns the name of a lint as written on the command line, i.e. in `kebab-case`.
+fn dashed(name: &str) -> String {
+    name.replace('_', "-")
+}
@@ -0,0 +1,599 @@
+//! Destinations of the log entries.
+//!
+//! Every [`Target`](crate::Target) writes the entries it receives to one or more [`Sink`]s.
+//! By default, a target writes to `stderr` (or to its `MultiProgress`, when the `indicatif`
+//! feature is enabled); more sinks can be added with [`Target::add_sink`](crate::Target::add_sink).
+//!
+//! Sinks may buffer their output: it is written only when the sink is flushed,
+//! e.g. through [`Target::flush`](crate::Target::flush), [`TargetList::flush`](crate::TargetList::flush)
+//! or, when the logger is installed with [`PrologueLogger::init`](crate::PrologueLogger::init),
+//! at the end of the program.
+//!
+//! To keep slow destinations from stalling the logging threads, a sink can be written from
+//! a dedicated thread by wrapping it into a [`BackgroundSink`].
+//!
+//! # Example
+//! ```
+//! # fn main() -> prologue_logger::error::Result<()> {
+//! # use prologue_logger::{Entry, Target};
+//! # use prologue_logger::sink::WriterSink;
+//! let target = Target::new("my-target");
+//! // Also write the entries, without colors, to a file.
+//! # let path = std::env::temp_dir().join("prologue-sink-example.log");
+//! let file = std::io::BufWriter::new(std::fs::File::create(&path)?);
+//! target.add_sink(WriterSink::new(file));
+//!
+//! Entry::new_warning("something needs your attention")
+//!     .log_to_target(&target)?;
+//!
+//! // Write the buffered contents to the file.
+//! target.flush()?;
+//! assert_eq!(std::fs::read_to_string(&path)?, "warning: something needs your attention\n");
+//! # Ok(()) }
+//! ```
+
+use std::collections::VecDeque;
+use std::fmt::{Debug, Formatter};
+use std::io::Write;
+use std::sync::{mpsc, Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+
+use crate::LogItem;
+use crate::error::{Error, Result};
+
+/// A destination of the log entries.
+///
+/// The same sink may receive entries from multiple targets and multiple threads at once.
+pub trait Sink: Send + Sync {
+    /// Writes the given `item`.
+    fn write(&self, item: &LogItem) -> Result<()>;
+
+    /// Writes the given `items` as one contiguous block, e.g. the entries held by
+    /// a [buffered](crate::Buffering) target.
+    ///
+    /// The default implementation writes the items one by one.
+    fn write_block(&self, items: &[LogItem]) -> Result<()> {
+        items.iter()
+            .map(|item| self.write(item))
+            .fold(Ok(()), Result::and)
+    }
+
+    /// Writes the given `item`, logged to the target called `target`.
+    ///
+    /// Targets write to their sinks through this method; the default implementation ignores
+    /// the name of the target and calls [`write`](Sink::write).
+    fn write_from(&self, target: &str, item: &LogItem) -> Result<()> {
+        let _ = target;
+        self.write(item)
+    }
+
+    /// Writes the given `items`, logged to the target called `target`, as one contiguous block.
+    ///
+    /// Targets write to their sinks through this method; the default implementation ignores
+    /// the name of the target and calls [`write_block`](Sink::write_block).
+    fn write_block_from(&self, target: &str, items: &[LogItem]) -> Result<()> {
+        let _ = target;
+        self.write_block(items)
+    }
+
+    /// Writes any buffered output to its final destination.
+    fn flush(&self) -> Result<()>;
+}
+
+/// Sink writing the entries to `stderr`, colored if the feature `console` is enabled.
+///
+/// This is the default sink of a [`Target`](crate::Target), unless the `indicatif`
+/// feature is enabled.
+///
+/// Every entry is rendered into a buffer first and then written at once while holding
+/// the lock of `stderr`, therefore entries logged concurrently by multiple threads
+/// never interleave.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct StderrSink;
+impl Sink for StderrSink {
+    fn write(&self, item: &LogItem) -> Result<()> {
+        self.write_block(std::slice::from_ref(item))
+    }
+
+    fn write_block(&self, items: &[LogItem]) -> Result<()> {
+        let text: String = items.iter().map(LogItem::to_string).collect();
+        std::io::stderr().lock().write_all(text.as_bytes())?;
+        Ok(())
+    }
+
+    fn flush(&self) -> Result<()> {
+        std::io::stderr().flush()?;
+        Ok(())
+    }
+}
+
+/// Sink writing the uncolored entries to any [`Write`] implementor, e.g. a file.
+///
+/// The writer is not buffered by the sink itself; wrap it into a
+/// [`BufWriter`](std::io::BufWriter) if needed.
+///
+/// Every entry is written with a single call to [`write_all`](Write::write_all)
+/// while holding the lock of the sink, therefore entries never interleave.
+pub struct WriterSink<W: Write + Send> {
+    writer: Mutex<W>
+}
+impl<W: Write + Send> Debug for WriterSink<W> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WriterSink").finish_non_exhaustive()
+    }
+}
+impl<W: Write + Send> WriterSink<W> {
+    /// Creates a new sink writing to `writer`.
+    pub fn new(writer: W) -> WriterSink<W> {
+        WriterSink { writer: Mutex::new(writer) }
+    }
+
+    /// Flushes the sink and returns the inner writer.
+    pub fn into_inner(self) -> Result<W> {
+        let mut writer = self.writer.into_inner().unwrap();
+        writer.flush()?;
+        Ok(writer)
+    }
+}
+impl<W: Write + Send> Sink for WriterSink<W> {
+    fn write(&self, item: &LogItem) -> Result<()> {
+        self.write_block(std::slice::from_ref(item))
+    }
+
+    fn write_block(&self, items: &[LogItem]) -> Result<()> {
+        let text: String = items.iter().map(LogItem::plain_text).collect();
+        self.writer.lock().unwrap().write_all(text.as_bytes())?;
+        Ok(())
+    }
+
+    fn flush(&self) -> Result<()> {
+        self.writer.lock().unwrap().flush()?;
+        Ok(())
+    }
+}
+
+/// Sink writing the entries as JSON objects, one per line, to any [`Write`] implementor.
+///
+/// The objects have the same structure as the [serialized](LogItem) items, with an additional
+/// `target` field containing the name of the target the entry was logged to, if any.
+/// The lines of a run can be read back with [`Run::from_jsonl`](crate::diff::Run::from_jsonl).
+///
+/// # Example
+/// ```
+/// # fn main() -> prologue_logger::error::Result<()> {
+/// # use prologue_logger::{Entry, Target};
+/// # use prologue_logger::sink::JsonSink;
+/// # let path = std::env::temp_dir().join("prologue-json-sink-example.jsonl");
+/// let target = Target::with_sink("my-target", JsonSink::new(std::fs::File::create(&path)?));
+/// Entry::new_warning("something needs your attention")
+///     .log_to_target(&target)?;
+///
+/// assert_eq!(
+///     std::fs::read_to_string(&path)?,
+///     r#"{"target":"my-target","type":"entry","kind":"warning","bright":false,"message":"something needs your attention"}"#.to_owned() + "\n"
+/// );
+/// # Ok(()) }
+/// ```
+#[cfg(feature = "json")]
+pub struct JsonSink<W: Write + Send> {
+    writer: Mutex<W>
+}
+#[cfg(feature = "json")]
+impl<W: Write + Send> Debug for JsonSink<W> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JsonSink").finish_non_exhaustive()
+    }
+}
+#[cfg(feature = "json")]
+impl<W: Write + Send> JsonSink<W> {
+    /// Creates a new sink writing to `writer`.
+    pub fn new(writer: W) -> JsonSink<W> {
+        JsonSink { writer: Mutex::new(writer) }
+    }
+
+    /// Flushes the sink and returns the inner writer.
+    pub fn into_inner(self) -> Result<W> {
+        let mut writer = self.writer.into_inner().unwrap();
+        writer.flush()?;
+        Ok(writer)
+    }
+
+    fn write_lines(&self, target: Option<&str>, items: &[LogItem]) -> Result<()> {
+        // Serialize first, so that the lines are written all at once.
+        let mut lines = Vec::new();
+        for item in items {
+            serde_json::to_writer(&mut lines, &JsonLine { target, item }).map_err(std::io::Error::from)?;
+            lines.push(b'\n');
+        }
+        self.writer.lock().unwrap().write_all(&lines)?;
+        Ok(())
+    }
+}
+#[cfg(feature = "json")]
+impl<W: Write + Send> Sink for JsonSink<W> {
+    fn write(&self, item: &LogItem) -> Result<()> {
+        self.write_lines(None, std::slice::from_ref(item))
+    }
+
+    fn write_block(&self, items: &[LogItem]) -> Result<()> {
+        self.write_lines(None, items)
+    }
+
+    fn write_from(&self, target: &str, item: &LogItem) -> Result<()> {
+        self.write_lines(Some(target), std::slice::from_ref(item))
+    }
+
+    fn write_block_from(&self, target: &str, items: &[LogItem]) -> Result<()> {
+        self.write_lines(Some(target), items)
+    }
+
+    fn flush(&self) -> Result<()> {
+        self.writer.lock().unwrap().flush()?;
+        Ok(())
+    }
+}
+
+/// Line written by a [`JsonSink`].
+#[cfg(feature = "json")]
+#[derive(serde::Serialize)]
+struct JsonLine<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target: Option<&'a str>,
+    #[serde(flatten)]
+    item: &'a LogItem
+}
+
+/// Sink printing the entries above the progress bars of a `MultiProgress`.
+///
+/// This is the default sink of a [`Target`](crate::Target) when the `indicatif` feature is enabled.
+#[cfg(feature = "indicatif")]
+#[derive(Clone, Debug)]
+pub struct MultiProgressSink(indicatif::MultiProgress);
+#[cfg(feature = "indicatif")]
+impl MultiProgressSink {
+    /// Creates a new sink printing above the progress bars of `multi_progress`.
+    pub fn new(multi_progress: indicatif::MultiProgress) -> MultiProgressSink {
+        MultiProgressSink(multi_progress)
+    }
+}
+#[cfg(feature = "indicatif")]
+impl Sink for MultiProgressSink {
+    fn write(&self, item: &LogItem) -> Result<()> {
+        self.0.println(format!("{}", item))?;
+        Ok(())
+    }
+
+    fn write_block(&self, items: &[LogItem]) -> Result<()> {
+        let text: String = items.iter().map(LogItem::to_string).collect();
+        self.0.println(text)?;
+        Ok(())
+    }
+
+    fn flush(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Behavior of a [`BackgroundSink`] when its queue is full.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, Default)]
+pub enum Backpressure {
+    /// The logging thread waits until the writer thread makes room in the queue.
+    #[default]
+    Block,
+    /// The oldest entry in the queue is discarded to make room for the new one.
+    DropOldest,
+    /// The entry being logged is discarded and only counted, so that logging never waits
+    /// and the entries already queued are all written.
+    ///
+    /// This is the "count dropped" policy: the number of discarded entries is reported
+    /// by [`BackgroundHandle::dropped`].
+    DropNewest
+}
+
+/// Sink handing the entries over to a dedicated thread, which writes them to another sink.
+///
+/// Formatting and writing the entries happen on the writer thread, therefore logging
+/// does not stall on slow destinations, e.g. slow terminals. The entries wait in a queue
+/// of bounded capacity, where a [block](Sink::write_block) of entries takes a single place;
+/// when the queue is full, the [`Backpressure`] policy decides whether
+/// the logging thread waits or some entry is discarded. Discarded entries are counted,
+/// see [`BackgroundHandle::dropped`].
+///
+/// Note that the warnings and errors are still counted by the [`Target`](crate::Target)
+/// as soon as they are logged, including the discarded ones.
+///
+/// Flushing the sink waits until the writer thread has written every entry queued so far
+/// and has flushed the inner sink, even after the queue has been closed. The writer thread stops when the sink is dropped or
+/// when [`BackgroundHandle::join`] is called, after writing the remaining entries.
+///
+/// # Example
+/// ```
+/// # fn main() -> prologue_logger::error::Result<()> {
+/// # use prologue_logger::{Entry, Target};
+/// # use prologue_logger::capture::Capture;
+/// # use prologue_logger::sink::{Backpressure, BackgroundSink};
+/// let capture = Capture::new();
+/// let (sink, handle) = BackgroundSink::new(capture.clone(), 1024, Backpressure::Block);
+/// let target = Target::with_sink("my-target", sink);
+///
+/// Entry::new_warning("written by the background thread")
+///     .log_to_target(&target)?;
+///
+/// // Write the remaining entries and stop the writer thread.
+/// handle.join()?;
+/// assert_eq!(capture.text(), "warning: written by the background thread\n");
+/// assert_eq!(handle.dropped(), 0);
+/// # Ok(()) }
+/// ```
+#[derive(Debug)]
+pub struct BackgroundSink {
+    queue: Arc<Queue>
+}
+impl BackgroundSink {
+    /// Creates a new sink writing to `sink` from a dedicated thread, queueing up to `capacity`
+    /// entries (at least one), and returns it along with the handle of the writer thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the writer thread cannot be spawned.
+    pub fn new<K: Sink + 'static>(sink: K, capacity: usize, backpressure: Backpressure) -> (BackgroundSink, BackgroundHandle) {
+        let queue = Arc::new(Queue::new(capacity.max(1), backpressure));
+        let writer_queue = queue.clone();
+        let thread = thread::Builder::new()
+            .name("prologue-writer".to_owned())
+            .spawn(move || writer_queue.run(sink))
+            .expect("could not spawn the writer thread");
+        let handle = BackgroundHandle { queue: queue.clone(), thread: Arc::new(Mutex::new(Some(thread))) };
+        (BackgroundSink { queue }, handle)
+    }
+}
+impl Drop for BackgroundSink {
+    fn drop(&mut self) {
+        self.queue.close();
+    }
+}
+impl Sink for BackgroundSink {
+    fn write(&self, item: &LogItem) -> Result<()> {
+        self.queue.push(Message::Item(None, Box::new(item.clone())));
+        Ok(())
+    }
+
+    fn write_block(&self, items: &[LogItem]) -> Result<()> {
+        self.queue.push(Message::Block(None, items.to_vec()));
+        Ok(())
+    }
+
+    fn write_from(&self, target: &str, item: &LogItem) -> Result<()> {
+        self.queue.push(Message::Item(Some(target.to_owned()), Box::new(item.clone())));
+        Ok(())
+    }
+
+    fn write_block_from(&self, target: &str, items: &[LogItem]) -> Result<()> {
+        self.queue.push(Message::Block(Some(target.to_owned()), items.to_vec()));
+        Ok(())
+    }
+
+    fn flush(&self) -> Result<()> {
+        self.queue.flush()
+    }
+}
+
+/// Handle of the writer thread of a [`BackgroundSink`].
+#[derive(Clone, Debug)]
+pub struct BackgroundHandle {
+    queue: Arc<Queue>,
+    thread: Arc<Mutex<Option<JoinHandle<Result<()>>>>>
+}
+impl BackgroundHandle {
+    /// Returns the number of entries discarded so far, either because the queue was full
+    /// or because they were logged after the writer thread stopped.
+    pub fn dropped(&self) -> usize {
+        self.queue.state.lock().unwrap().dropped
+    }
+
+    /// Waits until the writer thread has written every entry queued so far,
+    /// then flushes the inner sink.
+    ///
+    /// Once the sink is dropped or [`join`](BackgroundHandle::join) is called, waits instead
+    /// until the writer thread has written the remaining entries and stopped.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error encountered by the writer thread since the last flush, if any.
+    pub fn flush(&self) -> Result<()> {
+        self.queue.flush()
+    }
+
+    /// Stops the writer thread after it has written the remaining entries, and waits for it.
+    ///
+    /// Entries logged afterwards are discarded. Calling this method more than once has no effect.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error encountered by the writer thread since the last flush, if any.
+    pub fn join(&self) -> Result<()> {
+        self.queue.close();
+        match self.thread.lock().unwrap().take() {
+            Some(thread) => thread.join()
+                .unwrap_or_else(|_| Err(std::io::Error::other("the writer thread panicked").into())),
+            None => Ok(())
+        }
+    }
+}
+
+/// Message sent to the writer thread of a [`BackgroundSink`],
+/// along with the name of the target of the entries, if known.
+enum Message {
+    Item(Option<String>, Box<LogItem>),
+    Block(Option<String>, Vec<LogItem>),
+    Flush(mpsc::Sender<Result<()>>)
+}
+
+impl Message {
+    /// Returns `true` if the message contains entries, i.e. it takes a place in the queue.
+    fn is_entry(&self) -> bool {
+        !matches!(self, Message::Flush(_))
+    }
+
+    /// Returns the number of entries contained in the message.
+    fn len(&self) -> usize {
+        match self {
+            Message::Item(..) => 1,
+            Message::Block(_, items) => items.len(),
+            Message::Flush(_) => 0
+        }
+    }
+}
+
+/// Bounded queue between a [`BackgroundSink`] and its writer thread.
+struct Queue {
+    state: Mutex<QueueState>,
+    not_empty: Condvar,
+    not_full: Condvar,
+    stopped: Condvar,
+    capacity: usize,
+    backpressure: Backpressure
+}
+#[derive(Default)]
+struct QueueState {
+    messages: VecDeque<Message>,
+    items: usize,
+    dropped: usize,
+    closed: bool,
+    stopped: bool
+}
+impl Debug for Queue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Queue")
+            .field("capacity", &self.capacity)
+            .field("backpressure", &self.backpressure)
+            .finish_non_exhaustive()
+    }
+}
+impl Queue {
+    fn new(capacity: usize, backpressure: Backpressure) -> Queue {
+        Queue {
+            state: Mutex::new(QueueState::default()),
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+            stopped: Condvar::new(),
+            capacity, backpressure
+        }
+    }
+
+    fn push(&self, message: Message) {
+        let mut state = self.state.lock().unwrap();
+        while !state.closed && state.items >= self.capacity {
+            match self.backpressure {
+                Backpressure::Block => state = self.not_full.wait(state).unwrap(),
+                Backpressure::DropOldest => {
+                    // Flush requests are never discarded.
+                    if let Some(index) = state.messages.iter().position(Message::is_entry) {
+                        let oldest = state.messages.remove(index).unwrap();
+                        state.items -= 1;
+                        state.dropped += oldest.len();
+                    }
+                },
+                Backpressure::DropNewest => {
+                    state.dropped += message.len();
+                    return;
+                }
+            }
+        }
+        if state.closed {
+            state.dropped += message.len();
+            return;
+        }
+        state.messages.push_back(message);
+        state.items += 1;
+        self.not_empty.notify_one();
+    }
+
+    fn pop(&self) -> Option<Message> {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            if let Some(message) = state.messages.pop_front() {
+                if message.is_entry() {
+                    state.items -= 1;
+                    self.not_full.notify_one();
+                }
+                return Some(message);
+            }
+            if state.closed {
+                return None;
+            }
+            state = self.not_empty.wait(state).unwrap();
+        }
+    }
+
+    fn flush(&self) -> Result<()> {
+        let (sender, receiver) = mpsc::channel();
+        {
+            let mut state = self.state.lock().unwrap();
+            if state.closed {
+                // The writer thread writes the remaining entries and flushes the inner sink
+                // before stopping; its errors are reported by `BackgroundHandle::join`.
+                while !state.stopped {
+                    state = self.stopped.wait(state).unwrap();
+                }
+                return Ok(());
+            }
+            state.messages.push_back(Message::Flush(sender));
+            self.not_empty.notify_one();
+        }
+        receiver.recv()
+            .unwrap_or_else(|_| Err(std::io::Error::other("the writer thread stopped").into()))
+    }
+
+    fn close(&self) {
+        self.state.lock().unwrap().closed = true;
+        self.not_empty.notify_all();
+        self.not_full.notify_all();
+    }
+
+    /// Body of the writer thread.
+    fn run<K: Sink>(&self, sink: K) -> Result<()> {
+        // Stop accepting entries even if the sink panics, so that nobody waits for this thread.
+        struct Stop<'a>(&'a Queue);
+        impl Drop for Stop<'_> {
+            fn drop(&mut self) {
+                self.0.close();
+                let mut state = self.0.state.lock().unwrap_or_else(|e| e.into_inner());
+                state.messages.clear();
+                state.stopped = true;
+                self.0.stopped.notify_all();
+            }
+        }
+        let _stop = Stop(self);
+
+        let mut error: Option<Error> = None;
+        while let Some(message) = self.pop() {
+            match message {
+                Message::Item(target, item) => {
+                    let written = match target {
+                        Some(target) => sink.write_from(&target, &item),
+                        None => sink.write(&item)
+                    };
+                    if let Err(err) = written {
+                        error.get_or_insert(err);
+                    }
+                },
+                Message::Block(target, items) => {
+                    let written = match target {
+                        Some(target) => sink.write_block_from(&target, &items),
+                        None => sink.write_block(&items)
+                    };
+                    if let Err(err) = written {
+                        error.get_or_insert(err);
+                    }
+                },
+                Message::Flush(sender) => {
+                    let flushed = sink.flush();
+                    let _ = sender.send(error.take().map_or(flushed, Err));
+                }
+            }
+        }
+        let flushed = sink.flush();
+        error.map_or(flushed, Err)
+    }
+}
@@ -0,0 +1,254 @@
+//! Golden-file tests for the rendered log entries.
+//!
+//! A [`Snapshot`] compares the uncolored output recorded by a [`Capture`] with the contents
+//! of a golden file (usually with the `.stderr` extension, placed next to the test).
+//! If the two differ, the test panics and prints a line-by-line diff.
+//!
+//! When the environment variable `PROLOGUE_BLESS` is set (to any value other than `0`),
+//! the golden file is rewritten with the actual output instead, in the same way as
+//! the UI tests of the Rust compiler; this is also the way to create a new golden file.
+//! ```text
+//! PROLOGUE_BLESS=1 cargo test
+//! ```
+//!
+//! Before the comparison, the output can be normalized, e.g. to remove the
+//! line numbers or the platform-dependent parts of the paths.
+//!
+//! # Example
+//! ```no_run
+//! # use prologue_logger::{Entry, Target};
+//! # use prologue_logger::capture::Capture;
+//! # use prologue_logger::snapshot::Snapshot;
+//! let capture = Capture::new();
+//! let target = Target::with_capture("example", capture.clone());
+//!
+//! Entry::new_warning("something went wrong")
+//!     .log_to_target(&target);
+//!
+//! Snapshot::new("tests/ui/something_went_wrong.stderr")
+//!     .replace("\\", "/")
+//!     .normalize_line_numbers()
+//!     .assert_matches(&capture);
+//! ```
+
+use std::path::PathBuf;
+
+use crate::capture::Capture;
+
+/// Name of the environment variable which allows to rewrite the golden files.
+pub const BLESS_VAR: &str = "PROLOGUE_BLESS";
+
+type Normalizer = Box<dyn Fn(&str) -> String>;
+
+/// Comparison between the rendered log entries and a golden file.
+pub struct Snapshot {
+    path: PathBuf,
+    normalizers: Vec<Normalizer>
+}
+impl std::fmt::Debug for Snapshot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Snapshot")
+            .field("path", &self.path)
+            .field("normalizers", &self.normalizers.len())
+            .finish()
+    }
+}
+impl Snapshot {
+    /// Creates a new comparison with the golden file at the given `path`.
+    ///
+    /// Relative paths are resolved from the current directory which, when running `cargo test`,
+    /// is the root directory of the package.
+    pub fn new<P: Into<PathBuf>>(path: P) -> Snapshot {
+        Snapshot { path: path.into(), normalizers: Vec::new() }
+    }
+
+    /// Adds a normalization hook, which is applied to the whole output before the comparison.
+    ///
+    /// Hooks are applied in the order in which they are added.
+    ///
+    /// # Example
+    /// ```
+    /// # use prologue_logger::snapshot::Snapshot;
+    /// // Hide the name of the temporary directory.
+    /// let temp_dir = std::env::temp_dir().display().to_string();
+    /// let snapshot = Snapshot::new("tests/ui/temp.stderr")
+    ///     .normalize(move |text| text.replace(&temp_dir, "$TMP"));
+    /// ```
+    pub fn normalize<F: Fn(&str) -> String + 'static>(mut self, normalizer: F) -> Self {
+        self.normalizers.push(Box::new(normalizer));
+        self
+    }
+
+    /// Replaces every occurrence of `from` with `to` before the comparison.
+    ///
+    /// # Example
+    /// ```
+    /// # use prologue_logger::snapshot::Snapshot;
+    /// // Make the paths look the same on every platform.
+    /// let snapshot = Snapshot::new("tests/ui/paths.stderr")
+    ///     .replace("\\", "/");
+    /// ```
+    pub fn replace<S1: Into<String>, S2: Into<String>>(self, from: S1, to: S2) -> Self {
+        let (from, to) = (from.into(), to.into());
+        self.normalize(move |text| text.replace(&from, &to))
+    }
+
+    /// Replaces the line numbers with `LL` and the positions in `-->` references with `COL`,
+    /// so that the golden file does not need to change when some line is added to the source.
+    ///
+    /// # Example
+    /// ```
+    /// # use prologue_logger::snapshot::normalize_line_numbers;
+    /// let output = "\
+    /// warning: unused import: `std::io::Read`
+    ///   --> src/lib.rs:12:5
+    ///    |
+    /// 12 | use std::io::Read;
+    ///    |     ^^^^^^^^^^^^^
+    /// ";
+    /// assert_eq!(normalize_line_numbers(output), "\
+    /// warning: unused import: `std::io::Read`
+    ///   --> src/lib.rs:LL:COL
+    ///    |
+    /// LL | use std::io::Read;
+    ///    |     ^^^^^^^^^^^^^
+    /// ");
+    /// ```
+    pub fn normalize_line_numbers(self) -> Self {
+        self.normalize(normalize_line_numbers)
+    }
+
+    /// Compares the uncolored output recorded by `capture` with the golden file.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the output and the golden file differ, or if the golden file does not exist,
+    /// unless the `PROLOGUE_BLESS` environment variable is set.
+    #[track_caller]
+    pub fn assert_matches(&self, capture: &Capture) {
+        self.assert_text(capture.text());
+    }
+
+    /// Compares the given `text` with the golden file.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the text and the golden file differ, or if the golden file does not exist,
+    /// unless the `PROLOGUE_BLESS` environment variable is set.
+    #[track_caller]
+    pub fn assert_text<S: AsRef<str>>(&self, text: S) {
+        let actual = self.normalizers.iter()
+            .fold(text.as_ref().to_owned(), |text, normalizer| normalizer(&text));
+        let bless = std::env::var_os(BLESS_VAR).map(|v| v != "0").unwrap_or(false);
+        if bless {
+            if let Some(parent) = self.path.parent() {
+                std::fs::create_dir_all(parent)
+                    .unwrap_or_else(|err| panic!("could not create `{}`: {}", parent.display(), err));
+            }
+            std::fs::write(&self.path, actual)
+                .unwrap_or_else(|err| panic!("could not write `{}`: {}", self.path.display(), err));
+            return;
+        }
+        let expected = match std::fs::read_to_string(&self.path) {
+            Ok(expected) => expected,
+            Err(err) => panic!("could not read `{}`: {}\nrun with `{}=1` to create it; the actual output is:\n{}",
+                               self.path.display(), err, BLESS_VAR, actual)
+        };
+        if expected != actual {
+            panic!("the output differs from `{}`:\n{}\nrun with `{}=1` to update the golden file",
+                   self.path.display(), diff(&expected, &actual), BLESS_VAR);
+        }
+    }
+}
+
+/// Replaces the line numbers in the gutter with `LL` and the line and position in `-->`
+/// references with `LL:COL`, adjusting the indentation of the gutter accordingly.
+///
+/// This is the normalization applied by [`Snapshot::normalize_line_numbers`].
+pub fn normalize_line_numbers(text: &str) -> String {
+    const WIDTH: usize = 2;
+    let mut result = String::with_capacity(text.len());
+    // Width of the gutter of the last entry and whether the last line was a note.
+    let mut width = None;
+    let mut in_note = false;
+    for line in text.split_inclusive('\n') {
+        let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        let spaces = line[digits..].len() - line[digits..].trim_start_matches(' ').len();
+        let rest = &line[digits + spaces..];
+        if rest.starts_with("-->") && digits == 0 {
+            width = Some(spaces);
+            in_note = false;
+            result.push_str(&format!("{: >len$}", "", len = WIDTH));
+            result.push_str(&normalize_location(rest));
+        } else if (rest.starts_with('|') || rest.starts_with('=')) && (digits + spaces) > 0 {
+            width = Some(digits + spaces - 1);
+            in_note = rest.starts_with('=');
+            if digits > 0 {
+                result.push_str("LL");
+            } else {
+                result.push_str(&format!("{: >len$}", "", len = WIDTH));
+            }
+            result.push(' ');
+            result.push_str(rest);
+        } else if let (true, Some(w)) = (in_note, width) {
+            // Continuation of a multi-line note.
+            if spaces >= w + 9 && digits == 0 {
+                result.push_str(&format!("{: >len$}", "", len = WIDTH + 9));
+                result.push_str(&line[w + 9..]);
+            } else {
+                in_note = false;
+                result.push_str(line);
+            }
+        } else {
+            in_note = false;
+            result.push_str(line);
+        }
+    }
+    result
+}
+
+/// Replaces `file:line:position` with `file:LL:COL` in a `-->` reference.
+fn normalize_location(reference: &str) -> String {
+    let trimmed = reference.trim_end_matches(['\n', '\r']);
+    let ending = &reference[trimmed.len()..];
+    let mut parts = trimmed.rsplitn(3, ':');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(position), Some(line), Some(file))
+            if position.chars().all(|c| c.is_ascii_digit()) && line.chars().all(|c| c.is_ascii_digit()) =>
+            format!("{}:LL:COL{}", file, ending),
+        _ => reference.to_owned()
+    }
+}
+
+/// Produces a line-by-line diff between `expected` and `actual`.
+fn diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+    // Longest common subsequence table.
+    let mut lcs = vec![vec![0usize; actual.len() + 1]; expected.len() + 1];
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            lcs[i][j] = if expected[i] == actual[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    let mut result = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            result.push_str(&format!(" {}\n", expected[i]));
+            i += 1;
+            j += 1;
+        } else if i < expected.len() && (j == actual.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            result.push_str(&format!("-{}\n", expected[i]));
+            i += 1;
+        } else {
+            result.push_str(&format!("+{}\n", actual[j]));
+            j += 1;
+        }
+    }
+    result
+}
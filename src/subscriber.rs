@@ -0,0 +1,236 @@
+//! Integration with the [`tracing`](https://docs.rs/tracing/latest/tracing/) ecosystem.
+//!
+//! [`PrologueLayer`] is a [`Layer`] which displays the `tracing` events as log entries
+//! and routes them to the targets of a [`TargetList`], in the same way as the
+//! [`PrologueLogger`](crate::PrologueLogger) does with the records of the `log` crate.
+//!
+//! Events are converted as follows:
+//! * the level becomes the kind of the entry: `ERROR` is an error, `WARN` a warning,
+//!   `INFO` a note and `DEBUG` or `TRACE` a help;
+//! * the `message` field becomes the message of the entry;
+//! * the `file`, `line` and `col` (or `column`) fields become the `-->` location of the entry;
+//! * the `code` field becomes the [code](crate::Entry::code) of the entry;
+//! * any other field is appended to the message as `name=value`;
+//! * events with a `task` field are displayed as a [`Task`] instead, e.g.
+//!   `info!(task = "Compiling", "foo v0.1.0")`.
+//!
+//! # Example
+//! ```
+//! # fn main() -> prologue_logger::error::Result<()> {
+//! # use prologue_logger::{Target, TargetList};
+//! # use prologue_logger::capture::Capture;
+//! # use prologue_logger::subscriber::PrologueLayer;
+//! use tracing_subscriber::layer::SubscriberExt;
+//!
+//! let target_list = TargetList::new();
+//! // Record the entries of `my_app` to check them below.
+//! let capture = Capture::new();
+//! target_list.add_target(Target::with_capture("my_app", capture.clone()))?;
+//! let subscriber = tracing_subscriber::registry()
+//!     .with(PrologueLayer::new(target_list.clone()).enter_tasks(true));
+//!
+//! tracing::subscriber::with_default(subscriber, || {
+//!     let _span = tracing::info_span!(target: "my_app", "Checking", krate = "my_app v0.1.0").entered();
+//!     tracing::warn!(target: "my_app::config", file = "Config.toml", line = 3, col = 1, "unused key `edition`");
+//! });
+//!
+//! assert_eq!(target_list.find("my_app").unwrap().warning_count(), 1);
+//! assert_eq!(capture.text(), concat!(
+//!     "    Checking my_app v0.1.0\n",
+//!     "warning: unused key `edition`\n",
+//!     " --> Config.toml:3:1\n",
+//!     "\n"
+//! ));
+//! # Ok(()) }
+//! ```
+
+use std::fmt::Debug;
+
+use tracing::field::{Field, Visit};
+use tracing::{span, Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::{Entry, EntryKind, Source, Target, TargetList, Task};
+
+/// A [`Layer`] which displays the `tracing` events in the style of `cargo`.
+///
+/// Events are routed to the targets of a [`TargetList`] through [`TargetList::route`],
+/// using either the target of the event (the default) or the target of the innermost span
+/// containing the event (see [`route_by_span`](PrologueLayer::route_by_span)).
+/// Events which cannot be routed are discarded.
+#[derive(Clone, Debug)]
+pub struct PrologueLayer {
+    target_list: TargetList,
+    route_by_span: bool,
+    enter_tasks: bool,
+    close_task: Option<String>
+}
+impl PrologueLayer {
+    /// Creates a new layer which logs to the targets of `target_list`.
+    pub fn new(target_list: TargetList) -> PrologueLayer {
+        PrologueLayer { target_list, route_by_span: false, enter_tasks: false, close_task: None }
+    }
+
+    /// Routes the events using the target of the innermost span containing them,
+    /// instead of the target of the events themselves.
+    ///
+    /// Events outside of any span are still routed by their own target.
+    pub fn route_by_span(mut self, enabled: bool) -> Self {
+        self.route_by_span = enabled;
+        self
+    }
+
+    /// Displays a [`Task`] the first time a span is entered, using the name of the span as
+    /// the "verb" and the values of its fields as the description.
+    ///
+    /// For example, `info_span!("Compiling", krate = "foo v0.1.0")` is displayed as
+    /// ```text
+    ///    Compiling foo v0.1.0
+    /// ```
+    pub fn enter_tasks(mut self, enabled: bool) -> Self {
+        self.enter_tasks = enabled;
+        self
+    }
+
+    /// Displays a [`Task`] with the given "verb" when a span is closed,
+    /// using the values of its fields as the description, e.g. `Finished foo v0.1.0`.
+    pub fn close_tasks<S: Into<String>>(mut self, verb: S) -> Self {
+        self.close_task = Some(verb.into());
+        self
+    }
+
+    fn log_task(&self, target: &str, task: Task) {
+        if let Some(target) = self.target_list.route(target) {
+            target.log_inline_entry(task)
+                .expect("the logger encountered an `io` error and could not continue");
+        }
+    }
+}
+impl<S> Layer<S> for PrologueLayer where S: Subscriber + for<'a> LookupSpan<'a> {
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        if !self.enter_tasks && self.close_task.is_none() {
+            return;
+        }
+        let mut fields = Fields::default();
+        attrs.record(&mut fields);
+        if let Some(span) = ctx.span(id) {
+            let description = fields.others.into_iter()
+                .map(|(_, value)| value)
+                .chain(fields.message)
+                .collect::<Vec<_>>()
+                .join(" ");
+            span.extensions_mut().insert(SpanTask { description, entered: false });
+        }
+    }
+
+    fn on_enter(&self, id: &span::Id, ctx: Context<'_, S>) {
+        if !self.enter_tasks {
+            return;
+        }
+        if let Some(span) = ctx.span(id) {
+            let description = match span.extensions_mut().get_mut::<SpanTask>() {
+                Some(task) if !task.entered => {
+                    task.entered = true;
+                    task.description.clone()
+                },
+                _ => return
+            };
+            self.log_task(span.metadata().target(), Task::new(span.name(), description));
+        }
+    }
+
+    fn on_close(&self, id: span::Id, ctx: Context<'_, S>) {
+        if let (Some(verb), Some(span)) = (&self.close_task, ctx.span(&id)) {
+            let description = match span.extensions().get::<SpanTask>() {
+                Some(task) => task.description.clone(),
+                None => return
+            };
+            self.log_task(span.metadata().target(), Task::new(verb, description));
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let span_target = if self.route_by_span {
+            ctx.event_span(event).map(|span| span.metadata().target())
+        } else {
+            None
+        };
+        let target = match self.target_list.route(span_target.unwrap_or_else(|| event.metadata().target())) {
+            Some(target) => target,
+            None => return
+        };
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+        fields.log(*event.metadata().level(), &target)
+            .expect("the logger encountered an `io` error and could not continue");
+    }
+}
+
+/// Description of a span, used to display its [`Task`].
+struct SpanTask {
+    description: String,
+    entered: bool
+}
+
+/// Fields of an event or span.
+#[derive(Default)]
+struct Fields {
+    message: Option<String>,
+    file: Option<String>,
+    line: Option<usize>,
+    column: Option<usize>,
+    code: Option<String>,
+    task: Option<String>,
+    others: Vec<(&'static str, String)>
+}
+impl Fields {
+    fn record(&mut self, field: &Field, value: String) {
+        match field.name() {
+            "message" => self.message = Some(value),
+            "file" => self.file = Some(value),
+            "line" => self.line = value.parse().ok(),
+            "col" | "column" => self.column = value.parse().ok(),
+            "code" => self.code = Some(value),
+            "task" => self.task = Some(value),
+            name => self.others.push((name, value))
+        }
+    }
+
+    fn log(self, level: Level, target: &Target) -> crate::error::Result<()> {
+        let mut text = self.message.unwrap_or_default();
+        for (name, value) in self.others {
+            if !text.is_empty() {
+                text.push(' ');
+            }
+            text.push_str(&format!("{}={}", name, value));
+        }
+        if let Some(task) = self.task {
+            return target.log_inline_entry(Task::new(task, text));
+        }
+        let kind = match level {
+            Level::ERROR => EntryKind::Error,
+            Level::WARN => EntryKind::Warning,
+            Level::INFO => EntryKind::Note,
+            _ => EntryKind::Help
+        };
+        let mut entry = Entry::new(kind, text);
+        entry.code = self.code;
+        if let (Some(file), Some(line)) = (self.file, self.line) {
+            let mut source = Source::location(line, self.column.unwrap_or(1));
+            source.set_filename(file);
+            entry.bright = true;
+            entry.source = Some(source);
+        }
+        target.log_entry(entry)
+    }
+}
+impl Visit for Fields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.record(field, value.to_owned());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.record(field, format!("{:?}", value));
+    }
+}
@@ -0,0 +1,114 @@
+//! Rendering of log entries into SVG images.
+//!
+//! The image is generated from the same layout used to print the entries to `stderr`
+//! and it is colored with the colors of a [`Theme`].
+//! Every character is placed on a monospace grid, therefore the image looks exactly like
+//! the output in a terminal.
+//! Since SVG is a text format, the images are also well suited for snapshot tests
+//! which need to check the colors of the output.
+//!
+//! # Example
+//! ```
+//! # fn main() -> prologue_logger::error::Result<()> {
+//! # use prologue_logger::Entry;
+//! # use prologue_logger::svg::SvgImage;
+//! let mut image = SvgImage::new();
+//! image.add_entry(&Entry::new_warning("unused import: `std::io::Read`")
+//!     .named_source("src/lib.rs", 5, 5)
+//!     .new_line(5, "use std::io::Read;")
+//!     .annotate_warn(5, 13, "")?
+//!     .finish());
+//!
+//! // Obtain the image, e.g. to save it into the documentation.
+//! let svg = image.to_string();
+//! assert!(svg.starts_with("<svg"));
+//! assert!(svg.contains(r##"fill="#ffff55" font-weight="bold">warning</tspan>"##));
+//! # Ok(()) }
+//! ```
+
+use std::fmt::{Display, Formatter};
+
+use crate::{Entry, MultiEntry, Task};
+use crate::html::Escaped;
+use crate::layout::{Layout, Style};
+use crate::theme::Theme;
+
+const FONT_SIZE: usize = 15;
+const CELL_WIDTH: usize = 9;
+const LINE_HEIGHT: usize = 20;
+const PADDING: usize = 10;
+const BACKGROUND: &str = "#1e1e1e";
+const FOREGROUND: &str = "#d4d4d4";
+
+/// SVG image containing one or more log entries.
+///
+/// The image is obtained through the [`Display`](std::fmt::Display) trait.
+#[derive(Clone, Debug, Default)]
+pub struct SvgImage {
+    layout: Layout,
+    theme: Option<Theme>
+}
+impl Display for SvgImage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let theme = self.theme.unwrap_or_else(Theme::active);
+        let rows = self.layout.rows();
+        let columns = rows.iter()
+            .map(|row| row.spans.iter().map(|span| span.text.chars().count()).sum::<usize>())
+            .max()
+            .unwrap_or(0);
+        let width = columns * CELL_WIDTH + 2 * PADDING;
+        let height = rows.len() * LINE_HEIGHT + 2 * PADDING;
+        writeln!(f, "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" font-family=\"monospace\" font-size=\"{}\">",
+                 FONT_SIZE, w = width, h = height)?;
+        writeln!(f, "<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>", BACKGROUND)?;
+        for (index, row) in rows.iter().enumerate() {
+            let y = PADDING + (index + 1) * LINE_HEIGHT - (LINE_HEIGHT - FONT_SIZE);
+            write!(f, "<text y=\"{}\" fill=\"{}\" xml:space=\"preserve\">", y, FOREGROUND)?;
+            let mut column = 0;
+            for span in row.spans.iter() {
+                let len = span.text.chars().count();
+                let color = theme.color(span.style);
+                // Uncolored spaces do not need to be drawn.
+                if color.is_some() || !span.text.trim().is_empty() {
+                    write!(f, "<tspan x=\"{}\" textLength=\"{}\"", PADDING + column * CELL_WIDTH, len * CELL_WIDTH)?;
+                    if let Some(color) = color {
+                        write!(f, " fill=\"{}\"", color.hex())?;
+                    }
+                    if matches!(span.style, Style::Emphasis | Style::Severity(_) | Style::Task) {
+                        write!(f, " font-weight=\"bold\"")?;
+                    }
+                    write!(f, ">{}</tspan>", Escaped(&span.text))?;
+                }
+                column += len;
+            }
+            writeln!(f, "</text>")?;
+        }
+        writeln!(f, "</svg>")
+    }
+}
+impl SvgImage {
+    /// Creates a new, empty image which uses the [active theme](Theme::active).
+    pub fn new() -> SvgImage {
+        Default::default()
+    }
+
+    /// Uses the given `theme` instead of the active theme.
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = Some(theme);
+    }
+
+    /// Adds an [`Entry`] to the image.
+    pub fn add_entry(&mut self, entry: &Entry) {
+        entry.layout(&mut self.layout, None);
+    }
+
+    /// Adds a [`MultiEntry`] to the image.
+    pub fn add_multi_entry(&mut self, multi: &MultiEntry) {
+        multi.layout(&mut self.layout);
+    }
+
+    /// Adds a [`Task`] to the image.
+    pub fn add_task(&mut self, task: &Task) {
+        task.layout(&mut self.layout, None);
+    }
+}
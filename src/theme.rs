@@ -0,0 +1,199 @@
+//! Colors used to display the log entries.
+//!
+//! All the renderers (terminal, [HTML](crate::html), [SVG](crate::svg)) take their colors
+//! from a [`Theme`]; unless specified otherwise, the active theme is used,
+//! which by default reproduces the colors used by `cargo`.
+//!
+//! # Example
+//! ```
+//! # use prologue_logger::theme::{Color, Theme};
+//! // Display warnings in magenta instead of yellow.
+//! Theme::new()
+//!     .warning(Color::Magenta)
+//!     .set_active();
+//!
+//! assert_eq!(Theme::active(), Theme::new().warning(Color::Magenta));
+//! ```
+
+use std::fmt::Display;
+use std::sync::RwLock;
+
+#[cfg(not(feature = "console"))]
+use crate::internals::console;
+use crate::EntryKind;
+use crate::layout::Style;
+
+static ACTIVE_THEME: RwLock<Theme> = RwLock::new(Theme::new());
+
+/// A color of the palette.
+///
+/// In the terminal, colors are always displayed in their bright variant.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum Color {
+    /// Black (or dark gray).
+    Black,
+    /// Red.
+    Red,
+    /// Green.
+    Green,
+    /// Yellow.
+    Yellow,
+    /// Blue.
+    Blue,
+    /// Magenta.
+    Magenta,
+    /// Cyan.
+    Cyan,
+    /// White.
+    White
+}
+impl Color {
+    /// Returns the `#rrggbb` representation of the color,
+    /// used when rendering the log entries outside of a terminal.
+    ///
+    /// # Example
+    /// ```
+    /// # use prologue_logger::theme::Color;
+    /// assert_eq!(Color::Yellow.hex(), "#ffff55");
+    /// ```
+    pub fn hex(&self) -> &'static str {
+        match self {
+            Color::Black => "#555555",
+            Color::Red => "#ff5555",
+            Color::Green => "#55ff55",
+            Color::Yellow => "#ffff55",
+            Color::Blue => "#5555ff",
+            Color::Magenta => "#ff55ff",
+            Color::Cyan => "#55ffff",
+            Color::White => "#ffffff"
+        }
+    }
+
+    fn apply<D: Display>(&self, object: console::StyledObject<D>) -> console::StyledObject<D> {
+        let object = match self {
+            Color::Black => object.black(),
+            Color::Red => object.red(),
+            Color::Green => object.green(),
+            Color::Yellow => object.yellow(),
+            Color::Blue => object.blue(),
+            Color::Magenta => object.magenta(),
+            Color::Cyan => object.cyan(),
+            Color::White => object.white()
+        };
+        object.bright()
+    }
+}
+
+/// Set of colors used to display the log entries.
+///
+/// The default theme uses the same colors as `cargo`.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub struct Theme {
+    help: Color,
+    note: Color,
+    warning: Color,
+    error: Color,
+    gutter: Color,
+    emphasis: Color,
+    task: Color
+}
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::new()
+    }
+}
+impl Theme {
+    /// Creates the default theme, i.e. the one using the same colors as `cargo`.
+    pub const fn new() -> Theme {
+        Theme {
+            help: Color::Cyan,
+            note: Color::Green,
+            warning: Color::Yellow,
+            error: Color::Red,
+            gutter: Color::Cyan,
+            emphasis: Color::White,
+            task: Color::Green
+        }
+    }
+
+    /// Returns the active theme, i.e. the theme used when no other theme is specified.
+    pub fn active() -> Theme {
+        *ACTIVE_THEME.read().unwrap()
+    }
+
+    /// Makes this theme the active theme.
+    pub fn set_active(self) {
+        *ACTIVE_THEME.write().unwrap() = self;
+    }
+
+    /// Sets the color of help labels, annotations and underlines.
+    pub fn help(mut self, color: Color) -> Self {
+        self.help = color;
+        self
+    }
+
+    /// Sets the color of note labels, annotations and underlines.
+    pub fn note(mut self, color: Color) -> Self {
+        self.note = color;
+        self
+    }
+
+    /// Sets the color of warning labels, annotations and underlines.
+    pub fn warning(mut self, color: Color) -> Self {
+        self.warning = color;
+        self
+    }
+
+    /// Sets the color of error labels, annotations and underlines.
+    pub fn error(mut self, color: Color) -> Self {
+        self.error = color;
+        self
+    }
+
+    /// Sets the color of line numbers, bars and arrows on the left of the source code.
+    pub fn gutter(mut self, color: Color) -> Self {
+        self.gutter = color;
+        self
+    }
+
+    /// Sets the color of emphasized text, e.g. the message of an entry with a source.
+    pub fn emphasis(mut self, color: Color) -> Self {
+        self.emphasis = color;
+        self
+    }
+
+    /// Sets the color of the "verb" of a [`Task`](crate::Task).
+    pub fn task(mut self, color: Color) -> Self {
+        self.task = color;
+        self
+    }
+
+    /// Returns the color of the given `kind` of entry.
+    pub(crate) fn kind_color(&self, kind: EntryKind) -> Color {
+        match kind {
+            EntryKind::Help => self.help,
+            EntryKind::Note => self.note,
+            EntryKind::Warning => self.warning,
+            EntryKind::Error => self.error
+        }
+    }
+
+    /// Returns the color of the given `style`, or `None` if the style is not colored.
+    pub(crate) fn color(&self, style: Style) -> Option<Color> {
+        match style {
+            Style::Plain | Style::Code => None,
+            Style::Emphasis => Some(self.emphasis),
+            Style::Gutter => Some(self.gutter),
+            Style::Severity(kind) | Style::Underline(kind) | Style::Annotation(kind) => Some(self.kind_color(kind)),
+            Style::Task => Some(self.task)
+        }
+    }
+
+    /// Applies the terminal style to the given `text`.
+    pub(crate) fn paint<D: Display>(&self, style: Style, text: D) -> console::StyledObject<D> {
+        match self.color(style) {
+            Some(color) => color.apply(console::style(text)),
+            None => console::style(text)
+        }
+    }
+}
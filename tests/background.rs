@@ -0,0 +1,50 @@
+use std::thread;
+use std::time::Duration;
+
+use prologue_logger::{Entry, LogItem, Target};
+use prologue_logger::capture::Capture;
+use prologue_logger::sink::{Backpressure, BackgroundSink, Sink};
+
+/// Sink which records the entries after a delay, to keep them in flight for a while.
+struct SlowSink(Capture);
+impl Sink for SlowSink {
+    fn write(&self, item: &LogItem) -> prologue_logger::error::Result<()> {
+        thread::sleep(Duration::from_millis(20));
+        self.0.write(item)
+    }
+
+    fn flush(&self) -> prologue_logger::error::Result<()> {
+        self.0.flush()
+    }
+}
+
+#[test]
+fn flush_after_close_waits_for_queued_entries() {
+    let capture = Capture::new();
+    let (sink, handle) = BackgroundSink::new(SlowSink(capture.clone()), 16, Backpressure::Block);
+    let target = Target::with_sink("my-target", sink);
+    for i in 0..5 {
+        Entry::new_warning(format!("warning {}", i)).log_to_target(&target).unwrap();
+    }
+    // Dropping the last target closes the queue while the entries are still being written.
+    drop(target);
+
+    handle.flush().unwrap();
+    assert_eq!(capture.items().len(), 5);
+    handle.join().unwrap();
+}
+
+#[test]
+fn drop_newest_counts_the_discarded_entries() {
+    let capture = Capture::new();
+    let (sink, handle) = BackgroundSink::new(SlowSink(capture.clone()), 1, Backpressure::DropNewest);
+    let target = Target::with_sink("my-target", sink);
+    for i in 0..10 {
+        Entry::new_warning(format!("warning {}", i)).log_to_target(&target).unwrap();
+    }
+    handle.flush().unwrap();
+
+    assert_eq!(target.warning_count(), 10);
+    assert_eq!(capture.items().len() + handle.dropped(), 10);
+    assert!(handle.dropped() > 0);
+}
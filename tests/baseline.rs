@@ -0,0 +1,89 @@
+use prologue_logger::{Entry, MultiEntry, Target};
+use prologue_logger::baseline::Baseline;
+use prologue_logger::capture::Capture;
+use prologue_logger::error::ErrorKind;
+
+/// Logs the entries of a run of a linter, where `offset` lines were added at the top of the file.
+fn run(target: &Target, offset: usize, fixed: bool) -> prologue_logger::error::Result<()> {
+    Entry::new_warning("unused key `edition`\twith a tab")
+        .code("W0001")
+        .named_source("config dir/Config.toml", 3 + offset, 1)
+        .new_line(3 + offset, "edition = 2021")
+        .annotate_warn(1, 7, "")?
+        .finish()
+        .log_to_target(target)?;
+    if !fixed {
+        Entry::new_error("invalid value `42`\nexpected a string")
+            .named_source("config dir/Config.toml", 7 + offset, 8)
+            .finish()
+            .log_to_target(target)?;
+    }
+    MultiEntry::new()
+        .entry(Entry::new_warning("duplicate key `name`").named_source("Config.toml", 9 + offset, 1).finish())
+        .entry(Entry::new_note("first defined here").named_source("Config.toml", 1, 1).finish())
+        .log_to_target(target)
+}
+
+#[test]
+fn baseline_round_trip() {
+    let path = std::env::temp_dir().join(format!("prologue-baseline-{}.txt", std::process::id()));
+
+    let baseline = Baseline::new();
+    let target = Target::with_capture("first", Capture::new());
+    target.set_baseline(Some(baseline.clone()));
+    run(&target, 0, false).unwrap();
+    assert_eq!(target.baselined_count(), 0);
+    assert_eq!(target.warning_count(), 2);
+    baseline.recorded().save(&path).unwrap();
+
+    let baseline = Baseline::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(baseline.entries().len(), 3);
+    assert_eq!(baseline.entries()[1].message(), "invalid value `42`\nexpected a string");
+
+    let capture = Capture::new();
+    let target = Target::with_capture("second", capture.clone());
+    target.set_baseline(Some(baseline.clone()));
+    run(&target, 2, true).unwrap();
+    Entry::new_warning("unused key `authors`")
+        .log_to_target(&target)
+        .unwrap();
+    assert_eq!(target.baselined_count(), 2);
+    assert_eq!((target.warning_count(), target.error_count()), (1, 0));
+
+    baseline.log_fixed_to_target(&target).unwrap();
+    assert_eq!(capture.text(), concat!(
+        "warning: unused key `authors`\n",
+        "note: 1 entry was fixed since the baseline\n",
+        "note: fixed since the baseline: invalid value `42` in `config dir/Config.toml`\n"
+    ));
+}
+
+#[test]
+fn dash_fields_round_trip() {
+    let baseline = Baseline::new();
+    let target = Target::with_capture("first", Capture::new());
+    target.set_baseline(Some(baseline.clone()));
+    Entry::new_warning("unused key `edition`")
+        .code("-")
+        .named_source("-", 1, 1)
+        .finish()
+        .log_to_target(&target)
+        .unwrap();
+    Entry::new_warning("unused key `authors`")
+        .log_to_target(&target)
+        .unwrap();
+
+    let baseline = Baseline::parse(&baseline.recorded().to_string()).unwrap();
+    let entries = baseline.entries();
+    assert_eq!((entries[0].file(), entries[0].error_code()), (Some("-"), Some("-")));
+    assert_eq!((entries[1].file(), entries[1].error_code()), (None, None));
+}
+
+#[test]
+fn invalid_baseline() {
+    let error = Baseline::parse("# comment\n\n0123456789abcdef\t-\t-\tmessage\n0123\t-\t-\tmessage\n").unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::InvalidBaseline(4)));
+    let error = Baseline::parse("0123456789abcdef\t-\tmessage\n").unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::InvalidBaseline(1)));
+}
@@ -0,0 +1,38 @@
+#![cfg(feature = "log")]
+
+use std::process::Command;
+
+use prologue_logger::{Entry, PrologueLogger};
+
+const CHILD_VAR: &str = "PROLOGUE_BUILDER_CHILD";
+
+#[test]
+fn log_without_stderr() {
+    // Only does something when spawned by `disabled_stderr_writes_nowhere`.
+    if std::env::var_os(CHILD_VAR).is_none() {
+        return;
+    }
+    let logger = PrologueLogger::builder()
+        .stderr(false)
+        .target("t")
+        .build()
+        .unwrap();
+    let target = logger.find_target("t").unwrap();
+    Entry::new_warning("SHOULD NOT APPEAR")
+        .log_to_target(&target)
+        .unwrap();
+    target.flush().unwrap();
+    assert_eq!(target.warning_count(), 1);
+}
+
+#[test]
+fn disabled_stderr_writes_nowhere() {
+    let output = Command::new(std::env::current_exe().unwrap())
+        .args(["--exact", "log_without_stderr", "--nocapture", "--test-threads", "1"])
+        .env(CHILD_VAR, "1")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(!stderr.contains("SHOULD NOT APPEAR"), "unexpected output: {}", stderr);
+}
@@ -0,0 +1,56 @@
+use std::thread;
+
+use prologue_logger::{Entry, Target, TargetList};
+use prologue_logger::capture::Capture;
+
+const THREADS: usize = 8;
+const ERRORS: usize = 500;
+const LIMIT: usize = 10;
+
+/// Logs many errors to each target from its own thread, all at the same time.
+fn log_concurrently(targets: &[Target]) {
+    thread::scope(|scope| {
+        for (thread, target) in targets.iter().enumerate() {
+            scope.spawn(move || {
+                for index in 0..ERRORS {
+                    Entry::new_error(format!("thread {} error {}", thread, index))
+                        .log_to_target(target)
+                        .unwrap();
+                }
+            });
+        }
+    });
+}
+
+#[test]
+fn target_limit_is_never_exceeded() {
+    let capture = Capture::new();
+    let target = Target::with_capture("target", capture.clone());
+    target.set_error_limit(Some(LIMIT));
+    log_concurrently(&vec![target.clone(); THREADS]);
+
+    assert!(target.is_aborted());
+    assert_eq!(target.error_count(), LIMIT);
+    // The limited errors and the `aborting` entry.
+    assert_eq!(capture.error_count(), LIMIT + 1);
+}
+
+#[test]
+fn run_limit_is_never_exceeded() {
+    let capture = Capture::new();
+    let target_list = TargetList::new();
+    target_list.set_error_limit(Some(LIMIT));
+    let targets = (0..THREADS)
+        .map(|index| {
+            let target = Target::with_capture(format!("target-{}", index), capture.clone());
+            target_list.add_target(target.clone()).unwrap();
+            target
+        })
+        .collect::<Vec<_>>();
+    log_concurrently(&targets);
+
+    assert!(target_list.is_aborted());
+    assert!(targets.iter().all(Target::is_aborted));
+    assert_eq!(targets.iter().map(Target::error_count).sum::<usize>(), LIMIT);
+    assert_eq!(capture.error_count(), LIMIT + 1);
+}
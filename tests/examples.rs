@@ -0,0 +1,38 @@
+use prologue_logger::snapshot::Snapshot;
+
+#[cfg(feature = "log")]
+#[allow(dead_code)]
+#[path = "../examples/file.rs"]
+mod file;
+
+/// Logs the entries of `examples/file.rs` and returns the text it writes to `file.log`.
+#[cfg(feature = "log")]
+fn file_example_output() -> String {
+    use prologue_logger::Target;
+    use prologue_logger::capture::Capture;
+
+    let capture = Capture::new();
+    let target = Target::with_capture("file", capture.clone());
+    let (starting, running, warning) = file::entries().unwrap();
+    starting.log_to_target(&target).unwrap();
+    running.log_to_target(&target).unwrap();
+    warning.log_to_target(&target).unwrap();
+    capture.text()
+}
+
+#[cfg(feature = "log")]
+#[test]
+fn file_example() {
+    Snapshot::new("tests/examples/file.stderr")
+        .assert_text(file_example_output());
+}
+
+#[test]
+fn file_example_in_readme() {
+    // The contents of `file.log` shown in the README must be the output of the example.
+    let readme = std::fs::read_to_string("README.md").unwrap();
+    let (_, block) = readme.split_once("Contents of `file.log`:\n```text\n").unwrap();
+    let (block, _) = block.split_once("```").unwrap();
+    Snapshot::new("tests/examples/file.stderr")
+        .assert_text(block);
+}
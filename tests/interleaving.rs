@@ -0,0 +1,89 @@
+use std::process::Command;
+use std::thread;
+
+use prologue_logger::{Entry, LogItem, MultiEntry, Target};
+use prologue_logger::sink::StderrSink;
+
+const CHILD_VAR: &str = "PROLOGUE_INTERLEAVING_CHILD";
+const THREADS: usize = 8;
+const ENTRIES: usize = 200;
+
+/// Builds the `index`-th diagnostic of the given `thread`, spanning many lines.
+fn diagnostic(thread: usize, index: usize) -> prologue_logger::error::Result<MultiEntry> {
+    let file = format!("src/thread_{}.rs", thread);
+    let warning = Entry::new_warning(format!("thread {} entry {}", thread, index))
+        .named_source(file.clone(), index + 1, 9)
+        .new_line(index + 1, "    let unused = compute();")
+        .annotate_warn(9, 6, "help: if this is intentional, prefix it with an underscore")?
+        .new_line(index + 2, "    do_something_else();")
+        .note("`#[warn(unused_variables)]` on by default")
+        .help("this line is here to make the entry longer")
+        .finish();
+    let note = Entry::new_note("the function is defined here")
+        .named_source(file, index + 10, 1)
+        .new_line(index + 10, "fn compute() -> usize {")
+        .annotate_note(4, 7, "")?
+        .finish();
+    Ok(MultiEntry::new().entry(warning).entry(note))
+}
+
+/// Removes the ANSI escape sequences, in case colors are enabled.
+fn strip_colors(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            chars.by_ref().find(|c| c.is_ascii_alphabetic());
+        } else {
+            plain.push(c);
+        }
+    }
+    plain
+}
+
+#[test]
+fn log_concurrently_to_stderr() {
+    // Only does something when spawned by `concurrent_entries_do_not_interleave`.
+    if std::env::var_os(CHILD_VAR).is_none() {
+        return;
+    }
+    let target = Target::with_sink("stress", StderrSink);
+    thread::scope(|scope| {
+        for thread in 0..THREADS {
+            let target = &target;
+            scope.spawn(move || for index in 0..ENTRIES {
+                diagnostic(thread, index).unwrap()
+                    .log_to_target(target)
+                    .unwrap();
+            });
+        }
+    });
+}
+
+#[test]
+fn concurrent_entries_do_not_interleave() {
+    let output = Command::new(std::env::current_exe().unwrap())
+        .args(["--exact", "log_concurrently_to_stderr", "--nocapture", "--test-threads", "1"])
+        .env(CHILD_VAR, "1")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stderr = strip_colors(&String::from_utf8(output.stderr).unwrap());
+
+    // The output must be made of whole diagnostics only, one after the other.
+    let mut rest = stderr.as_str();
+    let mut seen = vec![0; THREADS];
+    while !rest.is_empty() {
+        let header = rest.lines().next().unwrap();
+        let (thread, index) = header.strip_prefix("warning: thread ")
+            .and_then(|header| header.split_once(" entry "))
+            .and_then(|(thread, index)| Some((thread.parse::<usize>().ok()?, index.parse::<usize>().ok()?)))
+            .unwrap_or_else(|| panic!("unexpected line in the output: {:?}", header));
+        let expected = LogItem::from(diagnostic(thread, index).unwrap()).plain_text();
+        assert!(rest.starts_with(&expected), "diagnostic interleaved with another one:\n{}", &rest[..expected.len().min(rest.len())]);
+        assert_eq!(seen[thread], index, "diagnostics of thread {} out of order", thread);
+        seen[thread] += 1;
+        rest = &rest[expected.len()..];
+    }
+    assert_eq!(seen, vec![ENTRIES; THREADS]);
+}